@@ -0,0 +1,133 @@
+use crate::parser::{Ecosystem, ParsedPackageJson};
+use crate::write::escape_xml;
+
+/// A library of the licenses screen with the text shown for it.
+struct LicenseEntry {
+    name: String,
+    text: String,
+}
+
+/// The license file and NOTICE of the package, or where to read its license when none was
+/// found, like the Gradle plugin does with the URL of a POM license.
+fn license_text(package: &ParsedPackageJson) -> String {
+    let texts: Vec<&str> = [&package.license_text, &package.notice_text]
+        .into_iter()
+        .map(|text| text.trim_end())
+        .filter(|text| !text.is_empty())
+        .collect();
+    if !texts.is_empty() {
+        return texts.join("\n\n");
+    }
+    if let Some(url) = [&package.declared_license_url, &package.license_url]
+        .into_iter()
+        .find(|url| url.starts_with("https://") || url.starts_with("http://"))
+    {
+        return url.clone();
+    }
+    match (package.known_license(), package.license.trim()) {
+        (Some(expression), _) => match expression.spdx_id() {
+            Some(id) => format!("https://spdx.org/licenses/{id}.html"),
+            None => expression.to_string(),
+        },
+        (None, "") => "No license information was found for this library.".to_string(),
+        (None, license) => license.to_string(),
+    }
+}
+
+/// The libraries an Android app ships, its Gradle and JavaScript dependencies, once per name
+/// and license text in the order of their names.
+fn license_entries(packages: &[ParsedPackageJson]) -> Vec<LicenseEntry> {
+    let mut sorted: Vec<&ParsedPackageJson> = packages
+        .iter()
+        .filter(|package| package.ecosystem != Ecosystem::CocoaPods)
+        .collect();
+    sorted.sort_by_key(|package| (package.name.to_lowercase(), package.key()));
+
+    let mut entries: Vec<LicenseEntry> = Vec::new();
+    for package in sorted {
+        let text = license_text(package);
+        if !entries
+            .iter()
+            .any(|entry| entry.name == package.name && entry.text == text)
+        {
+            entries.push(LicenseEntry {
+                name: package.name.clone(),
+                text,
+            });
+        }
+    }
+    entries
+}
+
+/// Builds the `res/raw/third_party_licenses` and `third_party_license_metadata` files of
+/// play-services-oss-licenses: the texts one after the other, and for each library the byte
+/// offset and length of its text followed by its name.
+pub(crate) fn to_oss_licenses(packages: &[ParsedPackageJson]) -> (String, String) {
+    let mut licenses = String::new();
+    let mut metadata = String::new();
+    // Libraries sharing a text point to the same bytes.
+    let mut offsets: Vec<(String, usize)> = Vec::new();
+    for entry in license_entries(packages) {
+        let offset = match offsets.iter().find(|(text, _)| *text == entry.text) {
+            Some((_, offset)) => *offset,
+            None => {
+                let offset = licenses.len();
+                licenses.push_str(&entry.text);
+                licenses.push('\n');
+                offsets.push((entry.text.clone(), offset));
+                offset
+            }
+        };
+        metadata.push_str(&format!("{offset}:{} {}\n", entry.text.len(), entry.name));
+    }
+    (licenses, metadata)
+}
+
+// Android string resources collapse whitespace and read `\`, quotes and a leading `@` or `?`
+// as escapes or references.
+fn android_string(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\'', "\\'")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t");
+    let escaped = match escaped.starts_with(['@', '?']) {
+        true => format!("\\{escaped}"),
+        false => escaped,
+    };
+    escape_xml(&escaped)
+}
+
+/// Builds a `res/values/licenses.xml` with the `third_party_license_names` and
+/// `third_party_license_texts` string arrays, an item of each per library in the same order.
+pub(crate) fn to_licenses_xml(packages: &[ParsedPackageJson]) -> String {
+    let entries = license_entries(packages);
+    let mut lines = vec![
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>".to_string(),
+        "<resources>".to_string(),
+    ];
+    for (array, values) in [
+        (
+            "third_party_license_names",
+            entries.iter().map(|entry| &entry.name).collect::<Vec<_>>(),
+        ),
+        (
+            "third_party_license_texts",
+            entries.iter().map(|entry| &entry.text).collect(),
+        ),
+    ] {
+        lines.push(format!(
+            "    <string-array name=\"{array}\" translatable=\"false\">"
+        ));
+        lines.extend(
+            values
+                .iter()
+                .map(|value| format!("        <item>{}</item>", android_string(value))),
+        );
+        lines.push("    </string-array>".to_string());
+    }
+    lines.push("</resources>".to_string());
+    lines.push(String::new());
+    lines.join("\n")
+}
@@ -1,37 +1,135 @@
 use std::env;
 use std::path::PathBuf;
 
-use clap::{arg, Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
 
 use crate::format_file_path;
+use crate::parser::Scope;
 
-#[derive(Debug, Clone, Copy, ValueEnum)]
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
 pub enum ManagersArgs {
     NPM,
     YARN,
     PNPM,
     IOS,
+    /// Artifacts of the build scripts and Gradle lockfiles, with those their POMs depend on
+    /// when cached in the Gradle cache or ~/.m2, license texts read from the cached jars
     ANDROID,
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum OutputFormat {
+    /// The license metadata of the packages as a JSON object keyed by `name@version`.
+    Json,
+    /// A CycloneDX 1.5 SBOM in JSON.
+    CyclonedxJson,
+    /// A CycloneDX 1.5 SBOM in XML.
+    CyclonedxXml,
+    /// An SPDX 2.3 document in JSON.
+    SpdxJson,
+    /// An SPDX 2.3 document in the tag-value format.
+    SpdxTagValue,
+    /// Plain-text third-party notices, the license texts with the packages under each.
+    Notice,
+    /// A table of the packages with collapsible license texts.
+    Markdown,
+    /// A self-contained page searchable and filterable by license and ecosystem.
+    Html,
+    /// A row for each package, for spreadsheets.
+    Csv,
+    /// The same rows as CSV separated by tabs.
+    Tsv,
+    /// The `raw/third_party_licenses` and `raw/third_party_license_metadata` resources of
+    /// play-services-oss-licenses, written in the `res` directory given as output.
+    AndroidOssLicenses,
+    /// A `values/licenses.xml` resource with the names and license texts as string arrays,
+    /// written in the `res` directory given as output.
+    AndroidLicensesXml,
+}
+
+impl OutputFormat {
+    /// Android resources go to a `res` directory, every other format to a
+    /// `dependencies-licenses` file with its extension.
+    fn default_output(&self) -> PathBuf {
+        let extension = match self {
+            OutputFormat::AndroidOssLicenses | OutputFormat::AndroidLicensesXml => {
+                return PathBuf::from("./res");
+            }
+            OutputFormat::Json | OutputFormat::CyclonedxJson | OutputFormat::SpdxJson => "json",
+            OutputFormat::CyclonedxXml => "xml",
+            OutputFormat::SpdxTagValue => "spdx",
+            OutputFormat::Notice => "txt",
+            OutputFormat::Markdown => "md",
+            OutputFormat::Html => "html",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Tsv => "tsv",
+        };
+        PathBuf::from(format!("./dependencies-licenses.{extension}"))
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Prints the chains of dependencies pulling a package in, from the projects depending on
+    /// it.
+    Explain {
+        /// `name` or `name@version` of the package.
+        package: String,
+    },
+    /// Checks the licenses against a policy, exiting with 1 when one is denied, unknown or not
+    /// allowed.
+    Check {
+        /// JSON or YAML file with the `allowed` and `denied` SPDX ids and the `exceptions`,
+        /// only GPL and AGPL licenses are denied when omitted.
+        #[arg(long)]
+        policy: Option<PathBuf>,
+    },
+}
+
 #[derive(Parser, Debug)]
 #[command(author="Kevin Tivert", version="0.0.1", about="license generator lib", long_about = None)]
 pub(crate) struct Cli {
-    #[arg(short = 'm', long)]
+    /// Package managers to scan, every one found in the project when omitted.
+    #[arg(short = 'm', long, value_delimiter = ',', global = true)]
     #[clap(value_enum)]
-    manager: ManagersArgs,
-    #[arg(short = 'p', long)]
+    manager: Vec<ManagersArgs>,
+    #[arg(short = 'p', long, default_value = ".", global = true)]
     #[clap(value_parser)]
     path: PathBuf,
-    #[arg(short, long, default_value = "./dependencies-licenses.json")]
+    /// File to write, or `res` directory for the Android formats. `./dependencies-licenses` with
+    /// the extension of the format, or `./res`, when omitted.
+    #[arg(short, long)]
     #[clap(value_parser)]
     output: Option<PathBuf>,
+    #[arg(short, long, default_value = "json")]
+    #[clap(value_enum)]
+    format: OutputFormat,
+    /// Workspace members to report on, by name or path, one report each.
+    #[arg(short, long, value_delimiter = ',', global = true)]
+    workspace: Vec<String>,
+    /// Dependency scopes to report, every one when omitted. `production` leaves out what is
+    /// only used to build or test the app.
+    #[arg(short, long, value_delimiter = ',', global = true)]
+    #[clap(value_enum)]
+    scope: Vec<Scope>,
+    /// Copies the text of license and NOTICE files into the report rather than only their
+    /// path.
+    #[arg(long)]
+    inline_license_text: bool,
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
 pub struct ParsedArgs {
-    pub manager: ManagersArgs,
+    pub managers: Vec<ManagersArgs>,
     pub root: PathBuf,
     pub output: PathBuf,
+    pub format: OutputFormat,
+    pub workspaces: Vec<String>,
+    pub scopes: Vec<Scope>,
+    pub inline_license_text: bool,
+    pub command: Option<Command>,
 }
 
 pub(crate) fn cli() -> ParsedArgs {
@@ -39,15 +137,26 @@ pub(crate) fn cli() -> ParsedArgs {
         manager,
         path,
         output,
+        format,
+        workspace,
+        scope,
+        inline_license_text,
+        command,
     } = Cli::parse();
 
     let cwd = env::current_dir().unwrap();
     let working_directory = format_file_path!(cwd.join(path));
-    let output_path = format_file_path!(working_directory.join(output.unwrap()));
+    let output = output.unwrap_or_else(|| format.default_output());
+    let output_path = format_file_path!(working_directory.join(output));
 
     ParsedArgs {
-        manager,
+        managers: manager,
         root: working_directory,
         output: output_path,
+        format,
+        workspaces: workspace,
+        scopes: scope,
+        inline_license_text,
+        command,
     }
 }
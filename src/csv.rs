@@ -0,0 +1,69 @@
+use crate::parser::ParsedPackageJson;
+
+const COLUMNS: [&str; 9] = [
+    "ecosystem",
+    "name",
+    "version",
+    "declared license",
+    "detected license",
+    "dependency",
+    "scope",
+    "repository",
+    "license file",
+];
+
+// Spreadsheets run cells starting like a formula, a leading `'` keeps free text from packages
+// as text. Names and versions are left as is, `@scope/name` is how they are looked up.
+fn free_text(value: &str) -> String {
+    match value.starts_with(['=', '+', '-', '@']) {
+        true => format!("'{value}"),
+        false => value.to_string(),
+    }
+}
+
+/// Quotes a CSV field holding a delimiter, quote or line break, doubling its quotes. TSV has no
+/// quoting, tabs and line breaks become spaces.
+fn field(value: &str, delimiter: char) -> String {
+    if delimiter == '\t' {
+        return value.replace(['\t', '\r', '\n'], " ");
+    }
+    match value.contains([delimiter, '"', '\r', '\n']) {
+        true => format!("\"{}\"", value.replace('"', "\"\"")),
+        false => value.to_string(),
+    }
+}
+
+/// Builds a table with a row for each package, separated by `,` for CSV or tabs for TSV.
+pub(crate) fn to_delimited(packages: &[ParsedPackageJson], delimiter: char) -> String {
+    let mut sorted: Vec<&ParsedPackageJson> = packages.iter().collect();
+    sorted.sort_by_key(|package| (package.ecosystem.as_str(), package.key()));
+
+    let separator = delimiter.to_string();
+    let mut lines = vec![COLUMNS.join(&separator)];
+    for package in sorted {
+        let detected_license = package
+            .detected_license
+            .as_ref()
+            .map(|detected| detected.license.to_string())
+            .unwrap_or_default();
+        let row = [
+            package.ecosystem.as_str().to_string(),
+            package.name.clone(),
+            package.version.clone(),
+            free_text(&package.license),
+            free_text(&detected_license),
+            match package.direct {
+                true => "direct",
+                false => "transitive",
+            }
+            .to_string(),
+            package.scope().as_str().to_string(),
+            free_text(&package.repository_url),
+            free_text(&package.license_url),
+        ];
+        let row: Vec<String> = row.iter().map(|value| field(value, delimiter)).collect();
+        lines.push(row.join(&separator));
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
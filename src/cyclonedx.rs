@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+
+use serde_json::{json, Map, Value};
+
+use crate::parser::{Ecosystem, ParsedPackageJson, Scope};
+use crate::write::{escape_xml, utc_timestamp};
+
+const SPEC_VERSION: &str = "1.5";
+
+enum LicenseChoice<'a> {
+    Id(&'a str),
+    Expression(String),
+    Name(&'a str),
+}
+
+// Licenses off the SPDX list keep the name the package declares.
+fn license_choice(package: &ParsedPackageJson) -> Option<LicenseChoice<'_>> {
+    match &package.license_expression {
+        Some(expression) => match expression.spdx_id() {
+            Some(id) => Some(LicenseChoice::Id(id)),
+            None => Some(LicenseChoice::Expression(expression.to_string())),
+        },
+        None => Some(package.license.trim())
+            .filter(|license| !license.is_empty())
+            .map(LicenseChoice::Name),
+    }
+}
+
+// Only urls point somewhere useful outside the project, license files are local paths.
+fn license_url(package: &ParsedPackageJson) -> Option<&str> {
+    [&package.declared_license_url, &package.license_url]
+        .into_iter()
+        .map(String::as_str)
+        .find(|url| url.starts_with("http"))
+}
+
+/// A `license` object naming the license by SPDX `id` or free-form `name`, with the license
+/// file text when the report inlines it.
+fn license_json(package: &ParsedPackageJson, field: &str, value: &str) -> Map<String, Value> {
+    let mut license = Map::new();
+    license.insert(field.to_string(), json!(value));
+    if !package.license_text.is_empty() {
+        license.insert(
+            "text".to_string(),
+            json!({ "contentType": "text/plain", "content": package.license_text }),
+        );
+    }
+    if let Some(url) = license_url(package) {
+        license.insert("url".to_string(), json!(url));
+    }
+    license
+}
+
+fn license_xml(package: &ParsedPackageJson, field: &str, value: &str) -> String {
+    let mut xml = String::from("      <licenses>\n        <license>\n");
+    xml.push_str(&format!(
+        "          <{field}>{}</{field}>\n",
+        escape_xml(value)
+    ));
+    if !package.license_text.is_empty() {
+        xml.push_str(&format!(
+            "          <text content-type=\"text/plain\">{}</text>\n",
+            escape_xml(&package.license_text)
+        ));
+    }
+    if let Some(url) = license_url(package) {
+        xml.push_str(&format!("          <url>{}</url>\n", escape_xml(url)));
+    }
+    xml.push_str("        </license>\n      </licenses>\n");
+    xml
+}
+
+fn external_references(package: &ParsedPackageJson) -> Vec<(&'static str, &str)> {
+    [
+        ("vcs", package.repository_url.as_str()),
+        ("website", package.homepage.as_str()),
+        ("distribution", package.resolved.as_str()),
+    ]
+    .into_iter()
+    // Yarn Berry records resolutions like `lodash@npm:4.17.21` rather than urls.
+    .filter(|(_, url)| url.contains("://"))
+    .collect()
+}
+
+// Development dependencies are not part of what is distributed.
+fn component_scope(package: &ParsedPackageJson) -> &'static str {
+    match package.scope() {
+        Scope::Production => "required",
+        Scope::Optional => "optional",
+        Scope::Development => "excluded",
+    }
+}
+
+fn sorted(packages: &[ParsedPackageJson]) -> Vec<&ParsedPackageJson> {
+    let mut packages: Vec<&ParsedPackageJson> = packages.iter().collect();
+    packages.sort_by_key(|package| package.purl());
+    packages
+}
+
+/// Maps every package to the purls of the packages it depends on.
+fn dependency_graph(packages: &[&ParsedPackageJson]) -> Vec<(String, Vec<String>)> {
+    let purls: HashMap<(Ecosystem, String), String> = packages
+        .iter()
+        .map(|package| ((package.ecosystem, package.key()), package.purl()))
+        .collect();
+
+    packages
+        .iter()
+        .map(|package| {
+            let mut depends_on: Vec<String> = package
+                .dependencies
+                .iter()
+                .filter_map(|key| purls.get(&(package.ecosystem, key.clone())).cloned())
+                .collect();
+            depends_on.sort();
+            (package.purl(), depends_on)
+        })
+        .collect()
+}
+
+/// Builds a CycloneDX 1.5 JSON bill of materials.
+pub(crate) fn to_json(packages: &[ParsedPackageJson]) -> Value {
+    let packages = sorted(packages);
+
+    let components: Vec<Value> = packages
+        .iter()
+        .map(|package| {
+            let mut component = Map::new();
+            component.insert("type".to_string(), json!("library"));
+            component.insert("bom-ref".to_string(), json!(package.purl()));
+            if !package.author.is_empty() {
+                component.insert("author".to_string(), json!(package.author));
+            }
+            if let Some(group) = package.group() {
+                component.insert("group".to_string(), json!(group));
+            }
+            component.insert("name".to_string(), json!(package.short_name()));
+            if !package.version.is_empty() {
+                component.insert("version".to_string(), json!(package.version));
+            }
+            if !package.description.is_empty() {
+                component.insert("description".to_string(), json!(package.description));
+            }
+            component.insert("scope".to_string(), json!(component_scope(package)));
+
+            let hashes: Vec<Value> = package
+                .hashes()
+                .into_iter()
+                .map(|(algorithm, content)| json!({ "alg": algorithm, "content": content }))
+                .collect();
+            if !hashes.is_empty() {
+                component.insert("hashes".to_string(), json!(hashes));
+            }
+
+            match license_choice(package) {
+                Some(LicenseChoice::Expression(expression)) => {
+                    component.insert(
+                        "licenses".to_string(),
+                        json!([{ "expression": expression }]),
+                    );
+                }
+                Some(LicenseChoice::Id(id)) => {
+                    component.insert(
+                        "licenses".to_string(),
+                        json!([{ "license": license_json(package, "id", id) }]),
+                    );
+                }
+                Some(LicenseChoice::Name(name)) => {
+                    component.insert(
+                        "licenses".to_string(),
+                        json!([{ "license": license_json(package, "name", name) }]),
+                    );
+                }
+                None => (),
+            }
+            if !package.copyrights.is_empty() {
+                component.insert(
+                    "copyright".to_string(),
+                    json!(package.copyrights.join("\n")),
+                );
+            }
+
+            component.insert("purl".to_string(), json!(package.purl()));
+
+            let references: Vec<Value> = external_references(package)
+                .into_iter()
+                .map(|(reference_type, url)| json!({ "type": reference_type, "url": url }))
+                .collect();
+            if !references.is_empty() {
+                component.insert("externalReferences".to_string(), json!(references));
+            }
+
+            Value::Object(component)
+        })
+        .collect();
+
+    let dependencies: Vec<Value> = dependency_graph(&packages)
+        .into_iter()
+        .map(|(reference, depends_on)| json!({ "ref": reference, "dependsOn": depends_on }))
+        .collect();
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": SPEC_VERSION,
+        "version": 1,
+        "metadata": {
+            "timestamp": utc_timestamp(),
+            "tools": {
+                "components": [{
+                    "type": "application",
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                }]
+            }
+        },
+        "components": components,
+        "dependencies": dependencies,
+    })
+}
+
+/// Builds a CycloneDX 1.5 XML bill of materials, with elements in schema order.
+pub(crate) fn to_xml(packages: &[ParsedPackageJson]) -> String {
+    let packages = sorted(packages);
+    let mut xml = String::new();
+
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<bom xmlns=\"http://cyclonedx.org/schema/bom/{SPEC_VERSION}\" version=\"1\">\n"
+    ));
+    xml.push_str("  <metadata>\n");
+    xml.push_str(&format!("    <timestamp>{}</timestamp>\n", utc_timestamp()));
+    xml.push_str("    <tools>\n      <components>\n");
+    xml.push_str("        <component type=\"application\">\n");
+    xml.push_str(&format!(
+        "          <name>{}</name>\n          <version>{}</version>\n",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    ));
+    xml.push_str("        </component>\n      </components>\n    </tools>\n");
+    xml.push_str("  </metadata>\n");
+
+    xml.push_str("  <components>\n");
+    for package in &packages {
+        let element =
+            |name: &str, value: &str| format!("      <{name}>{}</{name}>\n", escape_xml(value));
+
+        xml.push_str(&format!(
+            "    <component type=\"library\" bom-ref=\"{}\">\n",
+            escape_xml(&package.purl())
+        ));
+        if !package.author.is_empty() {
+            xml.push_str(&element("author", &package.author));
+        }
+        if let Some(group) = package.group() {
+            xml.push_str(&element("group", group));
+        }
+        xml.push_str(&element("name", package.short_name()));
+        if !package.version.is_empty() {
+            xml.push_str(&element("version", &package.version));
+        }
+        if !package.description.is_empty() {
+            xml.push_str(&element("description", &package.description));
+        }
+        xml.push_str(&element("scope", component_scope(package)));
+
+        let hashes = package.hashes();
+        if !hashes.is_empty() {
+            xml.push_str("      <hashes>\n");
+            for (algorithm, content) in hashes {
+                xml.push_str(&format!(
+                    "        <hash alg=\"{algorithm}\">{content}</hash>\n"
+                ));
+            }
+            xml.push_str("      </hashes>\n");
+        }
+
+        match license_choice(package) {
+            Some(LicenseChoice::Expression(expression)) => {
+                xml.push_str(&format!(
+                    "      <licenses>\n        <expression>{}</expression>\n      </licenses>\n",
+                    escape_xml(&expression)
+                ));
+            }
+            Some(LicenseChoice::Id(id)) => xml.push_str(&license_xml(package, "id", id)),
+            Some(LicenseChoice::Name(name)) => xml.push_str(&license_xml(package, "name", name)),
+            None => (),
+        }
+        if !package.copyrights.is_empty() {
+            xml.push_str(&element("copyright", &package.copyrights.join("\n")));
+        }
+
+        xml.push_str(&element("purl", &package.purl()));
+
+        let references = external_references(package);
+        if !references.is_empty() {
+            xml.push_str("      <externalReferences>\n");
+            for (reference_type, url) in references {
+                xml.push_str(&format!(
+                    "        <reference type=\"{reference_type}\">\n          <url>{}</url>\n        </reference>\n",
+                    escape_xml(url)
+                ));
+            }
+            xml.push_str("      </externalReferences>\n");
+        }
+        xml.push_str("    </component>\n");
+    }
+    xml.push_str("  </components>\n");
+
+    xml.push_str("  <dependencies>\n");
+    for (reference, depends_on) in dependency_graph(&packages) {
+        if depends_on.is_empty() {
+            xml.push_str(&format!(
+                "    <dependency ref=\"{}\"/>\n",
+                escape_xml(&reference)
+            ));
+            continue;
+        }
+        xml.push_str(&format!(
+            "    <dependency ref=\"{}\">\n",
+            escape_xml(&reference)
+        ));
+        for dependency in depends_on {
+            xml.push_str(&format!(
+                "      <dependency ref=\"{}\"/>\n",
+                escape_xml(&dependency)
+            ));
+        }
+        xml.push_str("    </dependency>\n");
+    }
+    xml.push_str("  </dependencies>\n");
+    xml.push_str("</bom>\n");
+    xml
+}
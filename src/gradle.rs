@@ -0,0 +1,536 @@
+use std::collections::HashMap;
+
+// Configurations whose artifacts are only used to build or test the app, never shipped with it.
+const BUILD_ONLY_CONFIGURATIONS: [&str; 7] = [
+    "classpath",
+    "annotationProcessor",
+    "kapt",
+    "ksp",
+    "lintChecks",
+    "compileOnly",
+    "detektPlugins",
+];
+
+// Dependency notations that do not resolve to a Maven artifact.
+const NON_MAVEN_NOTATIONS: [&str; 6] = [
+    "project(",
+    "fileTree(",
+    "files(",
+    "platform(",
+    "enforcedPlatform(",
+    "gradleApi(",
+];
+
+// Accessors of the `gradle/libs.versions.toml` version catalog, e.g. `libs.androidx.core`.
+const CATALOG_ACCESSOR: &str = "libs.";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct GradleDependency {
+    pub(crate) group: String,
+    pub(crate) artifact: String,
+    pub(crate) version: String,
+    // Declared in a build script, rather than only resolved in a lockfile.
+    pub(crate) declared: bool,
+}
+
+impl GradleDependency {
+    /// Parses a `group:artifact[:version[:classifier]][@extension]` coordinate.
+    pub(crate) fn from_coordinate(coordinate: &str) -> Option<GradleDependency> {
+        let coordinate = coordinate.split('@').next().unwrap_or(coordinate);
+        let mut parts = coordinate.split(':').map(str::trim);
+
+        let group = parts.next().filter(|group| !group.is_empty())?;
+        let artifact = parts.next().filter(|artifact| !artifact.is_empty())?;
+        let version = parts.next().unwrap_or("");
+
+        if group.contains(char::is_whitespace) || artifact.contains(char::is_whitespace) {
+            return None;
+        }
+
+        Some(GradleDependency {
+            group: group.to_string(),
+            artifact: artifact.to_string(),
+            // Versions left as `$variable` could not be resolved, do not report them.
+            version: if version.contains('$') {
+                String::new()
+            } else {
+                version.to_string()
+            },
+            declared: false,
+        })
+    }
+
+    pub(crate) fn coordinate(&self) -> String {
+        format!("{}:{}", self.group, self.artifact)
+    }
+}
+
+/// The libraries and bundles of a `gradle/libs.versions.toml`, by the accessor build scripts
+/// use, e.g. `androidx.core.ktx` for the `androidx-core-ktx` alias.
+#[derive(Debug, Default)]
+pub(crate) struct VersionCatalog {
+    // `group:artifact:version` coordinates.
+    libraries: HashMap<String, String>,
+    // Accessors of the libraries of each bundle.
+    bundles: HashMap<String, Vec<String>>,
+}
+
+// Gradle maps `-`, `_` and `.` in aliases to the same accessor separator.
+fn catalog_accessor(alias: &str) -> String {
+    alias.replace(['-', '_'], ".")
+}
+
+// A version is a string, or a table with a `ref` to `[versions]` or a rich version.
+fn catalog_version(value: Option<&toml::Value>, versions: &toml::Table) -> String {
+    match value {
+        Some(toml::Value::String(version)) => version.clone(),
+        Some(toml::Value::Table(table)) => match table.get("ref").and_then(toml::Value::as_str) {
+            Some(reference) => catalog_version(versions.get(reference), versions),
+            None => ["strictly", "require", "prefer"]
+                .iter()
+                .find_map(|key| table.get(*key).and_then(toml::Value::as_str))
+                .unwrap_or_default()
+                .to_string(),
+        },
+        _ => String::new(),
+    }
+}
+
+impl VersionCatalog {
+    /// Parses the `[libraries]` and `[bundles]` of a version catalog, resolving `version.ref`.
+    pub(crate) fn parse(content: &str) -> Result<VersionCatalog, toml::de::Error> {
+        let catalog: toml::Table = toml::from_str(content)?;
+        let section = |name: &str| {
+            catalog
+                .get(name)
+                .and_then(toml::Value::as_table)
+                .cloned()
+                .unwrap_or_default()
+        };
+        let versions = section("versions");
+
+        let mut libraries = HashMap::new();
+        for (alias, library) in section("libraries") {
+            let coordinate = match &library {
+                toml::Value::String(coordinate) => coordinate.clone(),
+                toml::Value::Table(table) => {
+                    let get = |key: &str| table.get(key).and_then(toml::Value::as_str);
+                    let module = match (get("module"), get("group"), get("name")) {
+                        (Some(module), _, _) => module.to_string(),
+                        (None, Some(group), Some(name)) => format!("{group}:{name}"),
+                        _ => continue,
+                    };
+                    format!(
+                        "{module}:{}",
+                        catalog_version(table.get("version"), &versions)
+                    )
+                }
+                _ => continue,
+            };
+            libraries.insert(catalog_accessor(&alias), coordinate);
+        }
+
+        let bundles = section("bundles")
+            .into_iter()
+            .map(|(alias, bundle)| {
+                let aliases = bundle
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(toml::Value::as_str)
+                    .map(catalog_accessor)
+                    .collect();
+                (catalog_accessor(&alias), aliases)
+            })
+            .collect();
+
+        Ok(VersionCatalog { libraries, bundles })
+    }
+
+    /// The artifacts a `libs.<accessor>` or `libs.bundles.<accessor>` notation stands for,
+    /// warning about aliases the catalog does not define.
+    fn resolve(&self, notation: &str) -> Vec<GradleDependency> {
+        let Some(start) = notation.find(CATALOG_ACCESSOR) else {
+            return Vec::new();
+        };
+        let accessor: String = notation[start + CATALOG_ACCESSOR.len()..]
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '.' || *c == '_')
+            .collect();
+        // Kotlin DSL providers may be unwrapped with `.get()`.
+        let accessor = accessor.trim_end_matches(".get").trim_end_matches('.');
+
+        let coordinates: Vec<&String> = match accessor.strip_prefix("bundles.") {
+            Some(bundle) => match self.bundles.get(bundle) {
+                Some(aliases) => aliases
+                    .iter()
+                    .filter_map(|alias| self.libraries.get(alias))
+                    .collect(),
+                None => Vec::new(),
+            },
+            None => self.libraries.get(accessor).into_iter().collect(),
+        };
+        if coordinates.is_empty() {
+            eprintln!(
+                "Skipping libs.{accessor}: not a library or bundle of gradle/libs.versions.toml"
+            );
+        }
+        coordinates
+            .into_iter()
+            .filter_map(|coordinate| GradleDependency::from_coordinate(coordinate))
+            .collect()
+    }
+}
+
+pub(crate) fn is_test_configuration(configuration: &str) -> bool {
+    configuration.to_lowercase().contains("test")
+}
+
+fn is_shipped_configuration(configuration: &str) -> bool {
+    !is_test_configuration(configuration) && !BUILD_ONLY_CONFIGURATIONS.contains(&configuration)
+}
+
+/// Removes `//` and `/* */` comments while leaving string literals untouched.
+pub(crate) fn strip_comments(content: &str) -> String {
+    let mut stripped = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut quote: Option<char> = None;
+
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) => {
+                stripped.push(c);
+                if c == '\\' {
+                    if let Some(escaped) = chars.next() {
+                        stripped.push(escaped);
+                    }
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None => match (c, chars.peek()) {
+                ('/', Some('/')) => {
+                    for c in chars.by_ref() {
+                        if c == '\n' {
+                            stripped.push('\n');
+                            break;
+                        }
+                    }
+                }
+                ('/', Some('*')) => {
+                    chars.next();
+                    let mut previous = ' ';
+                    for c in chars.by_ref() {
+                        if previous == '*' && c == '/' {
+                            break;
+                        }
+                        if c == '\n' {
+                            stripped.push('\n');
+                        }
+                        previous = c;
+                    }
+                }
+                ('"', _) | ('\'', _) => {
+                    quote = Some(c);
+                    stripped.push(c);
+                }
+                _ => stripped.push(c),
+            },
+        }
+    }
+    stripped
+}
+
+/// Returns the quoted string literals of a line, in order.
+fn string_literals(line: &str) -> Vec<String> {
+    let mut literals = Vec::new();
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '"' || c == '\'' {
+            let mut literal = String::new();
+            for inner in chars.by_ref() {
+                if inner == c {
+                    break;
+                }
+                literal.push(inner);
+            }
+            literals.push(literal);
+        }
+    }
+    literals
+}
+
+/// Returns the bodies of every `<keyword> { ... }` block, nested or not.
+fn blocks<'a>(content: &'a str, keyword: &str) -> Vec<&'a str> {
+    let mut bodies = Vec::new();
+    let bytes = content.as_bytes();
+    let mut search_from = 0;
+
+    while let Some(offset) = content[search_from..].find(keyword) {
+        let start = search_from + offset;
+        search_from = start + keyword.len();
+
+        let preceded_by_identifier = start > 0
+            && (bytes[start - 1].is_ascii_alphanumeric()
+                || bytes[start - 1] == b'_'
+                || bytes[start - 1] == b'.');
+        if preceded_by_identifier {
+            continue;
+        }
+
+        let rest = content[search_from..].trim_start();
+        if !rest.starts_with('{') {
+            continue;
+        }
+
+        let body_start = content.len() - rest.len() + 1;
+        let mut depth = 1;
+        let mut body_end = content.len();
+        for (index, c) in content[body_start..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        body_end = body_start + index;
+                        break;
+                    }
+                }
+                _ => (),
+            }
+        }
+        bodies.push(&content[body_start..body_end]);
+    }
+    bodies
+}
+
+/// Collects `name = 'value'` style variables, `ext.name = 'value'` and `set("name", "value")`
+/// so `$name` versions can be substituted.
+pub(crate) fn collect_variables(content: &str, variables: &mut HashMap<String, String>) {
+    for line in strip_comments(content).lines() {
+        let line = line.trim();
+
+        if let Some(arguments) = line.strip_prefix("set(") {
+            if let [name, value, ..] = string_literals(arguments).as_slice() {
+                variables.insert(name.clone(), value.clone());
+            }
+            continue;
+        }
+
+        let Some((name, value)) = line.split_once('=') else {
+            continue;
+        };
+        let name = name
+            .trim()
+            .trim_start_matches("def ")
+            .trim_start_matches("val ")
+            .trim_start_matches("var ")
+            .rsplit('.')
+            .next()
+            .unwrap_or("")
+            .trim();
+
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            continue;
+        }
+        if let Some(value) = string_literals(value).pop() {
+            variables.insert(name.to_string(), value);
+        }
+    }
+}
+
+/// Reads `key=value` pairs from a gradle.properties file.
+pub(crate) fn collect_properties(content: &str, variables: &mut HashMap<String, String>) {
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            variables.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+}
+
+fn substitute_variables(value: &str, variables: &HashMap<String, String>) -> String {
+    let mut substituted = String::new();
+    let mut rest = value;
+
+    while let Some(index) = rest.find('$') {
+        substituted.push_str(&rest[..index]);
+        rest = &rest[index + 1..];
+
+        let (expression, remaining) = if let Some(braced) = rest.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], &braced[end + 1..]),
+                None => (braced, ""),
+            }
+        } else {
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+                .unwrap_or(rest.len());
+            (&rest[..end], &rest[end..])
+        };
+
+        // `rootProject.ext.kotlinVersion` and `kotlinVersion` refer to the same variable.
+        let name = expression.rsplit('.').next().unwrap_or(expression);
+        match variables.get(name) {
+            Some(value) => substituted.push_str(value),
+            None => {
+                substituted.push('$');
+                substituted.push_str(expression);
+            }
+        }
+        rest = remaining;
+    }
+    substituted.push_str(rest);
+    substituted
+}
+
+fn named_argument(notation: &str, key: &str) -> Option<String> {
+    let mut search = notation;
+    while let Some(index) = search.find(key) {
+        search = &search[index + key.len()..];
+        let after_key = search.trim_start();
+        if let Some(value) = after_key
+            .strip_prefix(':')
+            .or_else(|| after_key.strip_prefix('='))
+        {
+            return string_literals(value).into_iter().next();
+        }
+    }
+    None
+}
+
+fn parse_dependency_line(
+    line: &str,
+    variables: &HashMap<String, String>,
+    catalog: &VersionCatalog,
+) -> Vec<GradleDependency> {
+    let line = line.trim();
+    let configuration_end = line
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(line.len());
+    let (configuration, notation) = line.split_at(configuration_end);
+
+    if configuration.is_empty() || !is_shipped_configuration(configuration) {
+        return Vec::new();
+    }
+    if NON_MAVEN_NOTATIONS
+        .iter()
+        .any(|prefix| notation.contains(prefix))
+    {
+        return Vec::new();
+    }
+    if notation.contains(CATALOG_ACCESSOR) {
+        return catalog.resolve(notation);
+    }
+
+    // Map notation: `group: 'g', name: 'a', version: 'v'` or `group = "g", name = "a", ...`.
+    if let (Some(group), Some(name)) = (
+        named_argument(notation, "group"),
+        named_argument(notation, "name"),
+    ) {
+        let version = named_argument(notation, "version").unwrap_or_default();
+        let coordinate = format!("{group}:{name}:{version}");
+        return GradleDependency::from_coordinate(&substitute_variables(&coordinate, variables))
+            .into_iter()
+            .collect();
+    }
+
+    string_literals(notation)
+        .iter()
+        .find(|literal| literal.contains(':'))
+        .and_then(|literal| {
+            GradleDependency::from_coordinate(&substitute_variables(literal, variables))
+        })
+        .into_iter()
+        .collect()
+}
+
+/// Parses every `dependencies {}` block of a Groovy or Kotlin DSL build script, resolving
+/// `libs.` accessors from the version catalog.
+pub(crate) fn parse_build_script(
+    content: &str,
+    variables: &HashMap<String, String>,
+    catalog: &VersionCatalog,
+) -> Vec<GradleDependency> {
+    let content = strip_comments(content);
+    let mut dependencies = Vec::new();
+
+    for body in blocks(&content, "dependencies") {
+        let mut depth = 0;
+        for line in body.lines() {
+            // Only top level statements are dependencies, nested blocks hold excludes
+            // and constraints.
+            if depth == 0 {
+                dependencies.extend(parse_dependency_line(line, variables, catalog));
+            }
+            depth += line.matches('{').count() as i32;
+            depth -= line.matches('}').count() as i32;
+            depth = depth.max(0);
+        }
+    }
+    dependencies
+}
+
+/// Returns the directory of every module included by a settings.gradle(.kts), relative to it.
+pub(crate) fn parse_settings(content: &str) -> Vec<String> {
+    let content = strip_comments(content);
+    let mut project_directories: HashMap<String, String> = HashMap::new();
+    let mut modules = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+
+        // project(':lib').projectDir = new File(rootProject.projectDir, '../node_modules/lib/android')
+        if line.starts_with("project(") && line.contains("projectDir") {
+            let literals = string_literals(line);
+            if let (Some(module), Some(directory)) = (literals.first(), literals.last()) {
+                if module != directory {
+                    project_directories.insert(module.clone(), directory.clone());
+                }
+            }
+            continue;
+        }
+
+        if line.starts_with("include") && !line.starts_with("includeBuild") {
+            modules.extend(string_literals(line));
+        }
+    }
+
+    modules
+        .into_iter()
+        .map(|module| match project_directories.get(&module) {
+            Some(directory) => directory.clone(),
+            None => module.trim_start_matches(':').replace(':', "/"),
+        })
+        .collect()
+}
+
+/// Parses a gradle.lockfile, keeping artifacts resolved for a shipped runtime classpath.
+pub(crate) fn parse_lockfile(content: &str) -> Vec<GradleDependency> {
+    let mut dependencies = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("empty=") {
+            continue;
+        }
+
+        let (coordinate, configurations) = line.split_once('=').unwrap_or((line, ""));
+        let is_shipped = configurations.is_empty()
+            || configurations.split(',').any(|configuration| {
+                !is_test_configuration(configuration)
+                    && configuration.to_lowercase().contains("runtimeclasspath")
+            });
+
+        if is_shipped {
+            if let Some(dependency) = GradleDependency::from_coordinate(coordinate) {
+                dependencies.push(dependency);
+            }
+        }
+    }
+    dependencies
+}
@@ -0,0 +1,224 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::parser::{Ecosystem, Importer, ParsedPackageJson, Scope};
+
+/// How a project pulls a package in.
+pub(crate) struct DependencyChain<'a> {
+    // The project, then the workspace members it reaches the first package through.
+    pub(crate) importers: Vec<&'a Importer>,
+    // The field the last project declares the first package in.
+    pub(crate) scope: Scope,
+    // Indexes of the packages, from the direct dependency to the explained package.
+    pub(crate) packages: Vec<usize>,
+}
+
+fn package_indexes(packages: &[ParsedPackageJson]) -> HashMap<(Ecosystem, String), usize> {
+    packages
+        .iter()
+        .enumerate()
+        .map(|(index, package)| ((package.ecosystem, package.key()), index))
+        .collect()
+}
+
+/// Flags the packages the projects depend on directly. Lockfiles the projects could not be read
+/// from fall back to the packages nothing else depends on.
+pub(crate) fn mark_direct_dependencies(packages: &mut [ParsedPackageJson], importers: &[Importer]) {
+    let direct: HashSet<(Ecosystem, &String)> = importers
+        .iter()
+        .flat_map(|importer| {
+            importer
+                .dependencies
+                .iter()
+                .map(move |(key, _)| (importer.ecosystem, key))
+        })
+        .collect();
+    let known_ecosystems: HashSet<Ecosystem> =
+        direct.iter().map(|(ecosystem, _)| *ecosystem).collect();
+    let depended_upon: HashSet<(Ecosystem, String)> = packages
+        .iter()
+        .flat_map(|package| {
+            package
+                .dependencies
+                .iter()
+                .map(|key| (package.ecosystem, key.clone()))
+        })
+        .collect();
+
+    for package in packages.iter_mut() {
+        let key = package.key();
+        package.direct = match known_ecosystems.contains(&package.ecosystem) {
+            true => direct.contains(&(package.ecosystem, &key)),
+            false => !depended_upon.contains(&(package.ecosystem, key)),
+        };
+    }
+}
+
+/// Returns the packages a `name` or `name@version` query designates.
+pub(crate) fn matching_packages(packages: &[ParsedPackageJson], query: &str) -> Vec<usize> {
+    let mut matches: Vec<usize> = (0..packages.len())
+        .filter(|&index| packages[index].key() == query || packages[index].name == query)
+        .collect();
+    matches.sort_by_key(|&index| (packages[index].ecosystem.as_str(), packages[index].key()));
+    matches
+}
+
+/// Finds how the projects pull `target` in: for every direct dependency leading to it, the
+/// shortest chain of packages from that dependency to the target.
+pub(crate) fn dependency_chains<'a>(
+    packages: &[ParsedPackageJson],
+    importers: &'a [Importer],
+    target: usize,
+) -> Vec<DependencyChain<'a>> {
+    let ecosystem = packages[target].ecosystem;
+    let indexes = package_indexes(packages);
+
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); packages.len()];
+    for (index, package) in packages.iter().enumerate() {
+        for key in &package.dependencies {
+            if let Some(&dependency) = indexes.get(&(package.ecosystem, key.clone())) {
+                dependents[dependency].push(index);
+            }
+        }
+    }
+    for package_dependents in dependents.iter_mut() {
+        package_dependents.sort_by_key(|&index| packages[index].key());
+    }
+
+    // Walking the dependents breadth first from the target, following `next` from any package
+    // reaching it is a shortest chain.
+    let mut next: HashMap<usize, usize> = HashMap::new();
+    let mut reached = HashSet::from([target]);
+    let mut pending = VecDeque::from([target]);
+    while let Some(index) = pending.pop_front() {
+        for &dependent in &dependents[index] {
+            if reached.insert(dependent) {
+                next.insert(dependent, index);
+                pending.push_back(dependent);
+            }
+        }
+    }
+
+    let importers: Vec<&Importer> = importers
+        .iter()
+        .filter(|importer| importer.ecosystem == ecosystem)
+        .collect();
+    let members: HashMap<&str, &Importer> = importers
+        .iter()
+        .map(|importer| (importer.path.as_str(), *importer))
+        .collect();
+
+    let mut chains = Vec::new();
+    for &importer in &importers {
+        // The workspace members the project depends on, each through the fewest links.
+        let mut routes: Vec<Vec<&Importer>> = vec![vec![importer]];
+        let mut visited = HashSet::from([importer.path.as_str()]);
+        let mut position = 0;
+        while position < routes.len() {
+            let route = routes[position].clone();
+            position += 1;
+            for path in &route[route.len() - 1].workspace_dependencies {
+                if let Some(&member) = members.get(path.as_str()) {
+                    if visited.insert(path.as_str()) {
+                        routes.push([route.clone(), vec![member]].concat());
+                    }
+                }
+            }
+        }
+
+        for route in routes {
+            for (key, scope) in &route[route.len() - 1].dependencies {
+                let Some(&first) = indexes.get(&(ecosystem, key.clone())) else {
+                    continue;
+                };
+                if !reached.contains(&first) {
+                    continue;
+                }
+                let mut chain = vec![first];
+                while let Some(&following) = next.get(chain.last().unwrap_or(&target)) {
+                    chain.push(following);
+                }
+                chains.push(DependencyChain {
+                    importers: route.clone(),
+                    scope: *scope,
+                    packages: chain,
+                });
+            }
+        }
+    }
+    chains
+}
+
+/// Names a project the way chains start with: node projects by their package name, native
+/// ones by their directory.
+pub(crate) fn project_name(importer: &Importer, root_directory: &Path) -> String {
+    let directory = root_directory.join(&importer.path);
+    match importer.ecosystem {
+        Ecosystem::Npm => fs::read_to_string(directory.join("package.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+            .and_then(|package_json| package_json["name"].as_str().map(str::to_string))
+            .or_else(|| {
+                let name = directory.canonicalize().ok()?.file_name()?.to_owned();
+                name.to_str().map(str::to_string)
+            })
+            .unwrap_or(importer.path.clone()),
+        Ecosystem::CocoaPods => format!("{} (CocoaPods)", importer.path),
+        Ecosystem::Maven => format!("{} (Gradle)", importer.path),
+    }
+}
+
+/// Describes why each package matching `query` is installed, one chain per line. Chains start
+/// from the workspace members at `member_paths`, or from every project when there are none.
+pub(crate) fn explain(
+    packages: &[ParsedPackageJson],
+    importers: &[Importer],
+    root_directory: &Path,
+    query: &str,
+    member_paths: &[String],
+) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for target in matching_packages(packages, query) {
+        let package = &packages[target];
+        lines.push(format!(
+            "{} ({}, {})",
+            package.key(),
+            package.ecosystem.as_str(),
+            package.scope().as_str()
+        ));
+
+        let mut chains: Vec<String> = dependency_chains(packages, importers, target)
+            .into_iter()
+            .filter(|chain| {
+                let project = chain.importers[0];
+                member_paths.is_empty()
+                    || project.ecosystem != Ecosystem::Npm
+                    || member_paths.contains(&project.path)
+            })
+            .map(|chain| {
+                let mut steps: Vec<String> = chain
+                    .importers
+                    .iter()
+                    .map(|importer| project_name(importer, root_directory))
+                    .collect();
+                if chain.scope != Scope::Production {
+                    let last = steps.len() - 1;
+                    steps[last] = format!("{} [{}]", steps[last], chain.scope.as_str());
+                }
+                steps.extend(chain.packages.iter().map(|&index| packages[index].key()));
+                format!("  {}", steps.join(" > "))
+            })
+            .collect();
+        chains.sort();
+        chains.dedup();
+        if chains.is_empty() {
+            chains.push("  No dependency chain leading to it is recorded".to_string());
+        }
+        lines.extend(chains);
+    }
+    lines
+}
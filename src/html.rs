@@ -0,0 +1,181 @@
+use std::collections::BTreeSet;
+
+use crate::markdown::{group_copyrights, package_groups, package_url};
+use crate::notice::license_groups;
+use crate::parser::ParsedPackageJson;
+use crate::write::escape_xml;
+
+const STYLE: &str = "
+body { font-family: system-ui, sans-serif; margin: 2rem auto; max-width: 64rem; padding: 0 1rem; color: #222; }
+.filters { display: flex; flex-wrap: wrap; gap: 0.5rem; margin-bottom: 1rem; }
+.filters input { flex: 1; min-width: 12rem; }
+.filters input, .filters select { font: inherit; padding: 0.3rem; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border-bottom: 1px solid #ddd; padding: 0.4rem; text-align: left; vertical-align: top; }
+pre { background: #f6f6f6; overflow-x: auto; padding: 1rem; white-space: pre-wrap; }
+summary { cursor: pointer; }
+[hidden] { display: none; }
+";
+
+// Hides the rows not matching the search and the filters, and the licenses left without a row.
+const SCRIPT: &str = "
+const search = document.getElementById('search');
+const license = document.getElementById('license');
+const ecosystem = document.getElementById('ecosystem');
+const rows = Array.from(document.querySelectorAll('#packages tbody tr'));
+const sections = Array.from(document.querySelectorAll('section.license'));
+const count = document.getElementById('count');
+
+function filter() {
+  const query = search.value.trim().toLowerCase();
+  const groups = new Set();
+  let shown = 0;
+  for (const row of rows) {
+    const visible = row.textContent.toLowerCase().includes(query)
+      && (!license.value || row.dataset.license === license.value)
+      && (!ecosystem.value || row.dataset.ecosystem === ecosystem.value);
+    row.hidden = !visible;
+    if (visible) {
+      groups.add(row.dataset.group);
+      shown += 1;
+    }
+  }
+  for (const section of sections) {
+    section.hidden = !groups.has(section.id);
+  }
+  count.textContent = shown + ' of ' + rows.length + ' packages';
+}
+
+search.addEventListener('input', filter);
+license.addEventListener('change', filter);
+ecosystem.addEventListener('change', filter);
+filter();
+";
+
+fn options(label: &str, values: &BTreeSet<&str>) -> String {
+    let mut options = vec![format!("<option value=\"\">{label}</option>")];
+    options.extend(values.iter().map(|value| {
+        let value = escape_xml(value);
+        format!("<option value=\"{value}\">{value}</option>")
+    }));
+    options.join("")
+}
+
+/// Builds a self-contained HTML page listing the packages, searchable and filterable by license
+/// and ecosystem, with the text of each license.
+pub(crate) fn to_html(packages: &[ParsedPackageJson], document_name: &str) -> String {
+    let groups = license_groups(packages);
+    let licenses: BTreeSet<&str> = groups.iter().map(|group| group.name.as_str()).collect();
+    let ecosystems: BTreeSet<&str> = packages
+        .iter()
+        .map(|package| package.ecosystem.as_str())
+        .collect();
+
+    // Rows link to the section of their license text.
+    let section_id = |index: usize| format!("license-{}", index + 1);
+    let mut rows = Vec::new();
+    for (package, index) in package_groups(&groups) {
+        let package_name = match package_url(package) {
+            "" => escape_xml(&package.name),
+            url => format!(
+                "<a href=\"{}\">{}</a>",
+                escape_xml(url),
+                escape_xml(&package.name)
+            ),
+        };
+        rows.push(format!(
+            "<tr data-license=\"{name}\" data-ecosystem=\"{ecosystem}\" data-group=\"{id}\">\
+             <td>{package_name}</td><td>{}</td><td>{ecosystem}</td>\
+             <td><a href=\"#{id}\">{name}</a></td></tr>",
+            escape_xml(&package.version),
+            name = escape_xml(&groups[index].name),
+            ecosystem = package.ecosystem.as_str(),
+            id = section_id(index),
+        ));
+    }
+
+    let mut sections = Vec::new();
+    for (index, group) in groups.iter().enumerate() {
+        let id = section_id(index);
+        let name = escape_xml(&group.name);
+        let package_keys: Vec<String> =
+            group.packages.iter().map(|package| package.key()).collect();
+        let mut section = vec![
+            format!("<section class=\"license\" id=\"{id}\">"),
+            format!("<h3>{name}</h3>"),
+            "<details>".to_string(),
+            format!(
+                "<summary>{}</summary>",
+                escape_xml(&package_keys.join(", "))
+            ),
+        ];
+        let copyrights = group_copyrights(&group.packages);
+        if !copyrights.is_empty() {
+            section.push("<ul>".to_string());
+            section.extend(
+                copyrights
+                    .iter()
+                    .map(|copyright| format!("<li>{}</li>", escape_xml(copyright))),
+            );
+            section.push("</ul>".to_string());
+        }
+        for package in group
+            .packages
+            .iter()
+            .filter(|package| !package.notice_text.is_empty())
+        {
+            section.push(format!(
+                "<p>NOTICE of {}:</p><pre>{}</pre>",
+                escape_xml(&package.key()),
+                escape_xml(package.notice_text.trim_end())
+            ));
+        }
+        match group.text.is_empty() {
+            true => section.push("<p>No license text was found in these packages.</p>".to_string()),
+            false => section.push(format!("<pre>{}</pre>", escape_xml(&group.text))),
+        }
+        section.extend(["</details>".to_string(), "</section>".to_string()]);
+        sections.push(section.join("\n"));
+    }
+
+    let document_name = escape_xml(document_name);
+    [
+        "<!DOCTYPE html>".to_string(),
+        "<html lang=\"en\">".to_string(),
+        "<head>".to_string(),
+        "<meta charset=\"utf-8\">".to_string(),
+        "<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">".to_string(),
+        "<title>Open source licenses</title>".to_string(),
+        format!("<style>{STYLE}</style>"),
+        "</head>".to_string(),
+        "<body>".to_string(),
+        "<h1>Open source licenses</h1>".to_string(),
+        format!("<p>{document_name} uses the following open source packages.</p>"),
+        "<div class=\"filters\">".to_string(),
+        "<input type=\"search\" id=\"search\" placeholder=\"Search packages\" aria-label=\"Search packages\">".to_string(),
+        format!(
+            "<select id=\"license\" aria-label=\"License\">{}</select>",
+            options("All licenses", &licenses)
+        ),
+        format!(
+            "<select id=\"ecosystem\" aria-label=\"Ecosystem\">{}</select>",
+            options("All ecosystems", &ecosystems)
+        ),
+        "</div>".to_string(),
+        "<p id=\"count\"></p>".to_string(),
+        "<table id=\"packages\">".to_string(),
+        "<thead><tr><th>Package</th><th>Version</th><th>Ecosystem</th><th>License</th></tr></thead>"
+            .to_string(),
+        "<tbody>".to_string(),
+        rows.join("\n"),
+        "</tbody>".to_string(),
+        "</table>".to_string(),
+        "<h2>Licenses</h2>".to_string(),
+        sections.join("\n"),
+        format!("<script>{SCRIPT}</script>"),
+        "</body>".to_string(),
+        "</html>".to_string(),
+        String::new(),
+    ]
+    .join("\n")
+}
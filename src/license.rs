@@ -0,0 +1,464 @@
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+// The SPDX license list, one id per line.
+const LICENSE_IDS: &str = include_str!("../data/spdx-license-ids.txt");
+const EXCEPTION_IDS: &str = include_str!("../data/spdx-exception-ids.txt");
+
+// What package metadata declares instead of SPDX ids, keyed by `alias_key`, with the
+// expression it stands for. Deprecated GNU ids map to their `-only` form.
+const LICENSE_ALIASES: [(&str, &str); 74] = [
+    ("mit", "MIT"),
+    ("expat", "MIT"),
+    ("mit x11", "MIT"),
+    ("apache", "Apache-2.0"),
+    ("apache2", "Apache-2.0"),
+    ("apache2.0", "Apache-2.0"),
+    ("apache 2", "Apache-2.0"),
+    ("apache 2.0", "Apache-2.0"),
+    ("asl 2.0", "Apache-2.0"),
+    ("apache 1.1", "Apache-1.1"),
+    // spdx-correct, which npm relies on, reads a bare BSD as the 2-clause license.
+    ("bsd", "BSD-2-Clause"),
+    ("simplified bsd", "BSD-2-Clause"),
+    ("freebsd", "BSD-2-Clause"),
+    ("bsd 2", "BSD-2-Clause"),
+    ("bsd 2 clause", "BSD-2-Clause"),
+    ("2 clause bsd", "BSD-2-Clause"),
+    ("new bsd", "BSD-3-Clause"),
+    ("bsd new", "BSD-3-Clause"),
+    ("modified bsd", "BSD-3-Clause"),
+    ("revised bsd", "BSD-3-Clause"),
+    ("bsd 3", "BSD-3-Clause"),
+    ("bsd 3 clause", "BSD-3-Clause"),
+    ("3 clause bsd", "BSD-3-Clause"),
+    ("isc", "ISC"),
+    ("cc0", "CC0-1.0"),
+    ("public domain cc0", "CC0-1.0"),
+    ("mpl 2", "MPL-2.0"),
+    ("mpl 2.0", "MPL-2.0"),
+    ("mozilla public 2.0", "MPL-2.0"),
+    ("mpl 1.1", "MPL-1.1"),
+    ("epl 1.0", "EPL-1.0"),
+    ("eclipse public 1.0", "EPL-1.0"),
+    ("epl 2.0", "EPL-2.0"),
+    ("eclipse public 2.0", "EPL-2.0"),
+    ("cddl", "CDDL-1.0"),
+    ("cddl 1.0", "CDDL-1.0"),
+    ("common development and distribution 1.0", "CDDL-1.0"),
+    ("cddl 1.1", "CDDL-1.1"),
+    (
+        "cddl + gplv2 with classpath exception",
+        "CDDL-1.1 OR GPL-2.0-only WITH Classpath-exception-2.0",
+    ),
+    ("boost", "BSL-1.0"),
+    ("boost 1.0", "BSL-1.0"),
+    ("python foundation", "PSF-2.0"),
+    ("gpl 2", "GPL-2.0-only"),
+    ("gpl 2.0", "GPL-2.0-only"),
+    ("gplv2", "GPL-2.0-only"),
+    ("gnu gpl 2", "GPL-2.0-only"),
+    ("gnu gplv2", "GPL-2.0-only"),
+    ("gnu general public 2", "GPL-2.0-only"),
+    ("gnu general public 2.0", "GPL-2.0-only"),
+    ("gnu general public 2 or later", "GPL-2.0-or-later"),
+    ("gpl 3", "GPL-3.0-only"),
+    ("gpl 3.0", "GPL-3.0-only"),
+    ("gplv3", "GPL-3.0-only"),
+    ("gnu gpl 3", "GPL-3.0-only"),
+    ("gnu gplv3", "GPL-3.0-only"),
+    ("gnu general public 3", "GPL-3.0-only"),
+    ("gnu general public 3.0", "GPL-3.0-only"),
+    ("gnu general public 3 or later", "GPL-3.0-or-later"),
+    ("lgpl 2.0", "LGPL-2.0-only"),
+    ("gnu library general public 2", "LGPL-2.0-only"),
+    ("lgpl 2.1", "LGPL-2.1-only"),
+    ("lgplv2.1", "LGPL-2.1-only"),
+    ("gnu lesser general public 2.1", "LGPL-2.1-only"),
+    ("lgpl 3", "LGPL-3.0-only"),
+    ("lgpl 3.0", "LGPL-3.0-only"),
+    ("lgplv3", "LGPL-3.0-only"),
+    ("gnu lesser general public 3", "LGPL-3.0-only"),
+    ("gnu lesser general public 3.0", "LGPL-3.0-only"),
+    ("agpl 1.0", "AGPL-1.0-only"),
+    ("agpl 3", "AGPL-3.0-only"),
+    ("agpl 3.0", "AGPL-3.0-only"),
+    ("agplv3", "AGPL-3.0-only"),
+    ("gnu affero general public 3", "AGPL-3.0-only"),
+    ("gnu affero general public 3.0", "AGPL-3.0-only"),
+];
+
+const EXCEPTION_ALIASES: [(&str, &str); 2] = [
+    ("classpath exception", "Classpath-exception-2.0"),
+    ("classpath exception 2.0", "Classpath-exception-2.0"),
+];
+
+// Left out of alias keys so `The Apache Software License, Version 2.0` reads as `apache 2.0`.
+const FILLER_WORDS: [&str; 8] = [
+    "the", "license", "licence", "licenses", "licensed", "version", "software", "v",
+];
+
+/// An SPDX license expression, e.g. `(MIT OR Apache-2.0) AND BSD-3-Clause`. Ids are canonical:
+/// SPDX ids as the license list writes them, `LicenseRef-` ids as declared.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum LicenseExpression {
+    // An id, ending with `+` for "this version or any later one" when it has no `-or-later`
+    // form.
+    License(String),
+    // A license and the exception it is granted with, e.g. `GPL-2.0-only WITH Classpath-exception-2.0`.
+    With(String, String),
+    And(Vec<LicenseExpression>),
+    Or(Vec<LicenseExpression>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    // Consecutive words between operators, e.g. `Apache 2.0`, resolved to an id by the parser.
+    Phrase(String),
+    And,
+    Or,
+    With,
+    Open,
+    Close,
+}
+
+fn tokenize(expression: &str) -> Vec<Token> {
+    let spaced = expression.replace('(', " ( ").replace(')', " ) ");
+    let mut tokens = Vec::new();
+    let mut words: Vec<&str> = Vec::new();
+
+    for word in spaced.split_whitespace() {
+        let token = match word {
+            "(" => Token::Open,
+            ")" => Token::Close,
+            _ if word.eq_ignore_ascii_case("AND") => Token::And,
+            _ if word.eq_ignore_ascii_case("OR") => Token::Or,
+            _ if word.eq_ignore_ascii_case("WITH") => Token::With,
+            _ => {
+                words.push(word);
+                continue;
+            }
+        };
+        if !words.is_empty() {
+            tokens.push(Token::Phrase(words.join(" ")));
+            words.clear();
+        }
+        tokens.push(token);
+    }
+    if !words.is_empty() {
+        tokens.push(Token::Phrase(words.join(" ")));
+    }
+    tokens
+}
+
+/// Reduces a license name to what tells licenses apart: lowercase words without punctuation
+/// or filler words, e.g. `The MIT License (MIT)` becomes `mit`.
+fn alias_key(name: &str) -> String {
+    let lowercase = name.to_ascii_lowercase();
+    let spaced: String = lowercase
+        .chars()
+        .map(|c| match c {
+            ',' | ';' | ':' | '_' | '-' | '/' | '(' | ')' | '"' | '\'' => ' ',
+            _ => c,
+        })
+        .collect();
+    let mut words: Vec<&str> = Vec::new();
+    for word in spaced.split_whitespace() {
+        // `v2.0` is the version `2.0`.
+        let word = match word.strip_prefix('v') {
+            Some(version) if version.starts_with(|c: char| c.is_ascii_digit()) => version,
+            _ => word,
+        };
+        if !FILLER_WORDS.contains(&word) && words.last() != Some(&word) {
+            words.push(word);
+        }
+    }
+    words.join(" ")
+}
+
+fn find_id(ids: &'static str, id: &str) -> Option<&'static str> {
+    ids.lines().find(|known| known.eq_ignore_ascii_case(id))
+}
+
+fn find_alias(aliases: &[(&str, &'static str)], name: &str) -> Option<&'static str> {
+    let key = alias_key(name);
+    aliases
+        .iter()
+        .find(|(alias, _)| *alias == key)
+        .map(|(_, canonical)| *canonical)
+}
+
+// `LicenseRef-` and `DocumentRef-<document>:LicenseRef-` ids name licenses off the SPDX list.
+fn is_license_ref(id: &str) -> bool {
+    let license_ref = match id.split_once(':') {
+        Some((document, license_ref)) if document.starts_with("DocumentRef-") => license_ref,
+        Some(_) => return false,
+        None => id,
+    };
+    license_ref.starts_with("LicenseRef-")
+        && license_ref
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+}
+
+/// Resolves what a phrase names to a canonical id: an SPDX id in any case, a `LicenseRef-`, or
+/// a known misspelling. A trailing `+` moves GNU licenses to their `-or-later` id.
+fn license_id(phrase: &str) -> Option<String> {
+    if is_license_ref(phrase) {
+        return Some(phrase.to_string());
+    }
+    let (name, or_later) = match phrase.strip_suffix('+') {
+        Some(name) => (name.trim_end(), true),
+        None => (phrase, false),
+    };
+    let id = find_alias(&LICENSE_ALIASES, name)
+        .filter(|alias| !alias.contains(' '))
+        .or_else(|| find_id(LICENSE_IDS, name))?;
+
+    Some(match (or_later, id.strip_suffix("-only")) {
+        (false, _) => id.to_string(),
+        (true, Some(base)) => format!("{base}-or-later"),
+        (true, None) if id.ends_with("-or-later") => id.to_string(),
+        (true, None) => format!("{id}+"),
+    })
+}
+
+fn exception_id(phrase: &str) -> Option<String> {
+    find_id(EXCEPTION_IDS, phrase)
+        .or_else(|| find_alias(&EXCEPTION_ALIASES, phrase))
+        .map(str::to_string)
+}
+
+/// Recursive descent over the SPDX grammar, where AND binds tighter than OR.
+struct ExpressionParser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl ExpressionParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn or_expression(&mut self) -> Option<LicenseExpression> {
+        let mut operands = vec![self.and_expression()?];
+        while self.peek() == Some(&Token::Or) {
+            self.position += 1;
+            operands.push(self.and_expression()?);
+        }
+        Some(match operands.len() {
+            1 => operands.remove(0),
+            _ => LicenseExpression::Or(operands),
+        })
+    }
+
+    fn and_expression(&mut self) -> Option<LicenseExpression> {
+        let mut operands = vec![self.term()?];
+        while self.peek() == Some(&Token::And) {
+            self.position += 1;
+            operands.push(self.term()?);
+        }
+        Some(match operands.len() {
+            1 => operands.remove(0),
+            _ => LicenseExpression::And(operands),
+        })
+    }
+
+    fn term(&mut self) -> Option<LicenseExpression> {
+        match self.next()? {
+            Token::Open => {
+                let expression = self.or_expression()?;
+                match self.next()? {
+                    Token::Close => Some(expression),
+                    _ => None,
+                }
+            }
+            Token::Phrase(phrase) => {
+                let license = license_id(&phrase)?;
+                if self.peek() != Some(&Token::With) {
+                    return Some(LicenseExpression::License(license));
+                }
+                self.position += 1;
+                match self.next()? {
+                    Token::Phrase(exception) => {
+                        Some(LicenseExpression::With(license, exception_id(&exception)?))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+impl LicenseExpression {
+    /// Parses a declared license into an SPDX expression, mapping common misspellings such as
+    /// `Apache 2.0` to their id. `None` when it names no known license, e.g.
+    /// `SEE LICENSE IN LICENSE.txt`.
+    pub(crate) fn parse(expression: &str) -> Option<LicenseExpression> {
+        // Names with operator words in them, e.g. `GNU General Public License v2 or later`.
+        let expression = find_alias(&LICENSE_ALIASES, expression).unwrap_or(expression);
+        let mut parser = ExpressionParser {
+            tokens: tokenize(expression),
+            position: 0,
+        };
+        let parsed = parser.or_expression()?;
+        match parser.position == parser.tokens.len() {
+            true => Some(parsed),
+            false => None,
+        }
+    }
+
+    /// The `LicenseRef-` ids of the expression, which SPDX documents must define. Those of
+    /// other documents, `DocumentRef-<document>:LicenseRef-`, are left out.
+    pub(crate) fn license_refs(&self) -> Vec<&str> {
+        match self {
+            LicenseExpression::License(id) | LicenseExpression::With(id, _) => {
+                match id.starts_with("LicenseRef-") {
+                    true => vec![id.as_str()],
+                    false => vec![],
+                }
+            }
+            LicenseExpression::And(operands) | LicenseExpression::Or(operands) => operands
+                .iter()
+                .flat_map(LicenseExpression::license_refs)
+                .collect(),
+        }
+    }
+
+    /// The SPDX id when the expression is a single license of the SPDX list, with no `+` or
+    /// exception.
+    pub(crate) fn spdx_id(&self) -> Option<&str> {
+        match self {
+            LicenseExpression::License(id) if find_id(LICENSE_IDS, id).is_some() => Some(id),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for LicenseExpression {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Compound operands are parenthesized so the string parses back to the same tree.
+        let operand = |expression: &LicenseExpression| match expression {
+            LicenseExpression::And(_) | LicenseExpression::Or(_) => format!("({expression})"),
+            _ => expression.to_string(),
+        };
+        match self {
+            LicenseExpression::License(license) => write!(formatter, "{license}"),
+            LicenseExpression::With(license, exception) => {
+                write!(formatter, "{license} WITH {exception}")
+            }
+            LicenseExpression::And(operands) => {
+                let operands: Vec<String> = operands.iter().map(operand).collect();
+                write!(formatter, "{}", operands.join(" AND "))
+            }
+            LicenseExpression::Or(operands) => {
+                let operands: Vec<String> = operands.iter().map(operand).collect();
+                write!(formatter, "{}", operands.join(" OR "))
+            }
+        }
+    }
+}
+
+// Reports hold the expression as its canonical string.
+impl Serialize for LicenseExpression {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for LicenseExpression {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let expression = String::deserialize(deserializer)?;
+        LicenseExpression::parse(&expression).ok_or_else(|| {
+            serde::de::Error::custom(format!("{expression} is not an SPDX license expression"))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LicenseExpression;
+
+    fn license(id: &str) -> LicenseExpression {
+        LicenseExpression::License(id.to_string())
+    }
+
+    #[test]
+    fn parses_license_names_as_spdx_ids() {
+        assert_eq!(
+            LicenseExpression::parse("Apache 2.0"),
+            Some(license("Apache-2.0"))
+        );
+        assert_eq!(LicenseExpression::parse("mit"), Some(license("MIT")));
+    }
+
+    #[test]
+    fn parses_parenthesized_expressions() {
+        assert_eq!(
+            LicenseExpression::parse("(MIT OR Apache-2.0)"),
+            Some(LicenseExpression::Or(vec![
+                license("MIT"),
+                license("Apache-2.0")
+            ]))
+        );
+    }
+
+    #[test]
+    fn parses_or_later_suffix() {
+        assert_eq!(
+            LicenseExpression::parse("GPL-2.0+"),
+            Some(license("GPL-2.0-or-later"))
+        );
+    }
+
+    #[test]
+    fn parses_nested_expressions() {
+        let expression = LicenseExpression::parse("MIT AND (BSD-2-Clause OR ISC)");
+        assert_eq!(
+            expression,
+            Some(LicenseExpression::And(vec![
+                license("MIT"),
+                LicenseExpression::Or(vec![license("BSD-2-Clause"), license("ISC")]),
+            ]))
+        );
+        assert_eq!(
+            expression.unwrap().to_string(),
+            "MIT AND (BSD-2-Clause OR ISC)"
+        );
+    }
+
+    #[test]
+    fn binds_and_tighter_than_or() {
+        assert_eq!(
+            LicenseExpression::parse("MIT OR Apache-2.0 AND BSD-3-Clause"),
+            Some(LicenseExpression::Or(vec![
+                license("MIT"),
+                LicenseExpression::And(vec![license("Apache-2.0"), license("BSD-3-Clause")]),
+            ]))
+        );
+    }
+
+    #[test]
+    fn parses_exceptions() {
+        assert_eq!(
+            LicenseExpression::parse("GPL-2.0-only WITH Classpath-exception-2.0"),
+            Some(LicenseExpression::With(
+                "GPL-2.0-only".to_string(),
+                "Classpath-exception-2.0".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn rejects_file_references() {
+        assert_eq!(LicenseExpression::parse("SEE LICENSE IN LICENSE.txt"), None);
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::license::LicenseExpression;
+
+// Texts of the SPDX license list for the licenses packages use the most.
+const LICENSE_TEXTS: [(&str, &str); 26] = [
+    ("0BSD", include_str!("../data/licenses/0BSD.txt")),
+    (
+        "AGPL-3.0-only",
+        include_str!("../data/licenses/AGPL-3.0-only.txt"),
+    ),
+    (
+        "Apache-1.1",
+        include_str!("../data/licenses/Apache-1.1.txt"),
+    ),
+    (
+        "Apache-2.0",
+        include_str!("../data/licenses/Apache-2.0.txt"),
+    ),
+    (
+        "Artistic-2.0",
+        include_str!("../data/licenses/Artistic-2.0.txt"),
+    ),
+    (
+        "BlueOak-1.0.0",
+        include_str!("../data/licenses/BlueOak-1.0.0.txt"),
+    ),
+    (
+        "BSD-2-Clause",
+        include_str!("../data/licenses/BSD-2-Clause.txt"),
+    ),
+    (
+        "BSD-3-Clause",
+        include_str!("../data/licenses/BSD-3-Clause.txt"),
+    ),
+    ("BSL-1.0", include_str!("../data/licenses/BSL-1.0.txt")),
+    ("CC-BY-4.0", include_str!("../data/licenses/CC-BY-4.0.txt")),
+    ("CC0-1.0", include_str!("../data/licenses/CC0-1.0.txt")),
+    ("CDDL-1.0", include_str!("../data/licenses/CDDL-1.0.txt")),
+    ("EPL-1.0", include_str!("../data/licenses/EPL-1.0.txt")),
+    ("EPL-2.0", include_str!("../data/licenses/EPL-2.0.txt")),
+    (
+        "GPL-2.0-only",
+        include_str!("../data/licenses/GPL-2.0-only.txt"),
+    ),
+    (
+        "GPL-3.0-only",
+        include_str!("../data/licenses/GPL-3.0-only.txt"),
+    ),
+    ("ISC", include_str!("../data/licenses/ISC.txt")),
+    (
+        "LGPL-2.1-only",
+        include_str!("../data/licenses/LGPL-2.1-only.txt"),
+    ),
+    (
+        "LGPL-3.0-only",
+        include_str!("../data/licenses/LGPL-3.0-only.txt"),
+    ),
+    ("MIT", include_str!("../data/licenses/MIT.txt")),
+    ("MIT-0", include_str!("../data/licenses/MIT-0.txt")),
+    ("MPL-2.0", include_str!("../data/licenses/MPL-2.0.txt")),
+    (
+        "Python-2.0",
+        include_str!("../data/licenses/Python-2.0.txt"),
+    ),
+    ("Unlicense", include_str!("../data/licenses/Unlicense.txt")),
+    ("WTFPL", include_str!("../data/licenses/WTFPL.txt")),
+    ("Zlib", include_str!("../data/licenses/Zlib.txt")),
+];
+
+// Below it the file is more likely a different license or several bundled together.
+const MINIMUM_CONFIDENCE: f64 = 0.9;
+
+/// A license identified from the text of a package's license file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct DetectedLicense {
+    pub(crate) license: LicenseExpression,
+    // How close the file is to the license text, from 0 to 1.
+    pub(crate) confidence: f64,
+    // Relative to the project root, empty when the text was not read from a file.
+    pub(crate) file: String,
+}
+
+// Words following `copyright` when a wrapped line of the license itself starts with it.
+const COPYRIGHT_CLAUSE_WORDS: [&str; 8] = [
+    "notice", "notices", "holder", "holders", "owner", "owners", "law", "laws",
+];
+
+// A year or range of years, e.g. `2018,` or `2010-2020`.
+fn is_year(word: &str) -> bool {
+    word.chars().take_while(char::is_ascii_digit).count() == 4
+}
+
+/// Whether a line states who holds the copyright, e.g. `Copyright (c) 2018 Jane Doe`, rather
+/// than being a wrapped line of the license starting with `copyright notice`. A line starting
+/// with `(c)` only does when a year follows, lettered clauses of licenses start the same way,
+/// e.g. `(c) You must retain` in Apache-2.0.
+pub(crate) fn is_copyright_line(line: &str) -> bool {
+    let line = line.trim_start();
+    if let Some(rest) = line
+        .strip_prefix("(c)")
+        .or_else(|| line.strip_prefix("(C)"))
+    {
+        return is_year(rest.split_whitespace().next().unwrap_or_default());
+    }
+    if let Some(rest) = line.strip_prefix('©') {
+        return !rest.trim().is_empty();
+    }
+    let Some(rest) = line
+        .strip_prefix("Copyright")
+        .or_else(|| line.strip_prefix("COPYRIGHT"))
+    else {
+        return false;
+    };
+    let next_word = rest
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase();
+    !COPYRIGHT_CLAUSE_WORDS.contains(&next_word.as_str())
+}
+
+/// The pairs of consecutive words of a license text, lowercased and without punctuation or
+/// copyright lines, which differ from one package to the next.
+fn word_pairs(text: &str) -> WordPairs {
+    let words: Vec<String> = text
+        .lines()
+        .filter(|line| !is_copyright_line(line))
+        .flat_map(|line| {
+            line.split(|c: char| !c.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .map(|word| match word.to_lowercase().as_str() {
+                    "licence" => "license".to_string(),
+                    word => word.to_string(),
+                })
+                .collect::<Vec<String>>()
+        })
+        .collect();
+    words
+        .windows(2)
+        .map(|pair| (pair[0].clone(), pair[1].clone()))
+        .collect()
+}
+
+type WordPairs = HashSet<(String, String)>;
+
+fn corpus() -> &'static Vec<(&'static str, WordPairs)> {
+    static CORPUS: OnceLock<Vec<(&'static str, WordPairs)>> = OnceLock::new();
+    CORPUS.get_or_init(|| {
+        LICENSE_TEXTS
+            .iter()
+            .map(|(id, text)| (*id, word_pairs(text)))
+            .collect()
+    })
+}
+
+/// Identifies the license a text is, by the Sørensen–Dice coefficient of its word pairs and
+/// those of each license of the corpus. `None` when no license is close enough.
+pub(crate) fn detect_license(text: &str) -> Option<DetectedLicense> {
+    let pairs = word_pairs(text);
+    if pairs.is_empty() {
+        return None;
+    }
+
+    let (id, confidence) = corpus()
+        .iter()
+        .map(|(id, license_pairs)| {
+            let shared = pairs.intersection(license_pairs).count();
+            let confidence = 2.0 * shared as f64 / (pairs.len() + license_pairs.len()) as f64;
+            (*id, confidence)
+        })
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+
+    match confidence >= MINIMUM_CONFIDENCE {
+        true => Some(DetectedLicense {
+            license: LicenseExpression::License(id.to_string()),
+            confidence: (confidence * 100.0).round() / 100.0,
+            file: String::new(),
+        }),
+        false => None,
+    }
+}
@@ -1,46 +1,325 @@
-use crate::cli::{cli, ParsedArgs};
-use crate::parser::{handle_dependencies_files, parse_lock_file};
-use crate::write::write_node_dependencies_to_file;
+use crate::cli::{cli, Command, ManagersArgs, OutputFormat, ParsedArgs};
+use crate::graph::{explain, mark_direct_dependencies};
+use crate::parser::{
+    find_dependency_file, handle_dependencies_files, merge_installed_copies, parse_lock_file,
+    Ecosystem, Importer, ParsedPackageJson, Scope,
+};
+use crate::policy::Policy;
+use crate::workspace::{reachable_packages, workspace_members, WorkspaceMember};
+use crate::write::{
+    write_android_licenses_xml_to_dir, write_android_oss_licenses_to_dir,
+    write_cyclonedx_json_to_file, write_cyclonedx_xml_to_file, write_delimited_to_file,
+    write_html_to_file, write_markdown_to_file, write_node_dependencies_to_file,
+    write_notice_to_file, write_spdx_json_to_file, write_spdx_tag_value_to_file,
+};
 use serde_json::Value;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::process;
 
+mod android;
 mod cli;
+mod csv;
+mod cyclonedx;
+mod gradle;
+mod graph;
+mod html;
+mod license;
+mod license_text;
 mod macros;
+mod markdown;
+mod maven;
+mod notice;
 mod parser;
+mod pnp;
+mod policy;
+mod spdx;
+mod workspace;
 mod write;
 
+fn write_report(
+    format: OutputFormat,
+    parsed_dependencies: &[ParsedPackageJson],
+    document_name: &str,
+    output_path: &str,
+) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => {
+            let mut key_counts: HashMap<String, usize> = HashMap::new();
+            for pkg in parsed_dependencies.iter() {
+                *key_counts.entry(pkg.key()).or_default() += 1;
+            }
+
+            let parsed_dependencies_json: serde_json::Map<String, Value> = parsed_dependencies
+                .iter()
+                .map(|pkg| {
+                    let pkg_value = serde_json::to_value(pkg).expect("Failed to serialize package");
+                    // Qualify keys only when packages of two ecosystems share a name and version.
+                    let key = match key_counts[&pkg.key()] {
+                        1 => pkg.key(),
+                        _ => format!("{}:{}", pkg.ecosystem.as_str(), pkg.key()),
+                    };
+                    (key, pkg_value)
+                })
+                .collect();
+
+            write_node_dependencies_to_file(parsed_dependencies_json, output_path)
+        }
+        OutputFormat::CyclonedxJson => {
+            write_cyclonedx_json_to_file(parsed_dependencies, output_path)
+        }
+        OutputFormat::CyclonedxXml => write_cyclonedx_xml_to_file(parsed_dependencies, output_path),
+        OutputFormat::SpdxJson => {
+            write_spdx_json_to_file(parsed_dependencies, document_name, output_path)
+        }
+        OutputFormat::SpdxTagValue => {
+            write_spdx_tag_value_to_file(parsed_dependencies, document_name, output_path)
+        }
+        OutputFormat::Notice => write_notice_to_file(parsed_dependencies, output_path),
+        OutputFormat::Markdown => {
+            write_markdown_to_file(parsed_dependencies, document_name, output_path)
+        }
+        OutputFormat::Html => write_html_to_file(parsed_dependencies, document_name, output_path),
+        OutputFormat::Csv => write_delimited_to_file(parsed_dependencies, ',', output_path),
+        OutputFormat::Tsv => write_delimited_to_file(parsed_dependencies, '\t', output_path),
+        OutputFormat::AndroidOssLicenses => {
+            write_android_oss_licenses_to_dir(parsed_dependencies, output_path)
+        }
+        OutputFormat::AndroidLicensesXml => {
+            write_android_licenses_xml_to_dir(parsed_dependencies, output_path)
+        }
+    }
+}
+
+/// `dependencies-licenses.json` becomes `dependencies-licenses-<member>.json` when several
+/// workspace members are reported.
+fn member_output_path(output_path: &str, member_name: &str) -> String {
+    let suffix: String = member_name
+        .trim_start_matches('@')
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let path = Path::new(output_path);
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("dependencies");
+    let file_name = match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) => format!("{stem}-{suffix}.{extension}"),
+        None => format!("{stem}-{suffix}"),
+    };
+    path.with_file_name(file_name)
+        .to_str()
+        .unwrap_or(output_path)
+        .to_string()
+}
+
+/// Finds the workspace members the `--workspace` selectors name, by package name or path.
+fn select_members(root: &Path, workspaces: &[String]) -> Vec<WorkspaceMember> {
+    let members = workspace_members(root);
+    let mut selected = Vec::new();
+    for selector in workspaces.iter() {
+        let selected_path = selector.trim_start_matches("./").trim_end_matches('/');
+        match members
+            .iter()
+            .find(|member| member.name == *selector || member.path == selected_path)
+        {
+            Some(member) => selected.push(member.clone()),
+            None => eprintln!("No workspace member named {selector} in {:?}", root),
+        }
+    }
+    selected
+}
+
+/// Gathers the packages of a workspace member: the JavaScript packages it reaches, the native
+/// ones of the root and those of its own ios and android projects.
+fn member_dependencies(
+    member: &WorkspaceMember,
+    parsed_dependencies: &[ParsedPackageJson],
+    importers: &[Importer],
+    managers: &[ManagersArgs],
+    scopes: &[Scope],
+    root: &Path,
+) -> Vec<ParsedPackageJson> {
+    let mut member_dependencies = reachable_packages(parsed_dependencies, importers, &member.path);
+    member_dependencies.extend(
+        parsed_dependencies
+            .iter()
+            .filter(|pkg| pkg.ecosystem != Ecosystem::Npm)
+            .cloned(),
+    );
+    let mut member_importers: Vec<Importer> = importers
+        .iter()
+        .filter(|importer| importer.ecosystem != Ecosystem::Npm || importer.path == member.path)
+        .cloned()
+        .collect();
+    let member_directory = root.join(&member.path);
+    for manager in [ManagersArgs::IOS, ManagersArgs::ANDROID] {
+        if !managers.is_empty() && !managers.contains(&manager) {
+            continue;
+        }
+        if let Some(path) = find_dependency_file(manager, &member_directory)
+            .as_ref()
+            .and_then(|path| path.to_str())
+        {
+            let parsed_lockfile = parse_lock_file(manager, root, path);
+            member_dependencies.extend(
+                parsed_lockfile
+                    .packages
+                    .into_iter()
+                    .filter(|pkg| scopes.is_empty() || scopes.contains(&pkg.scope())),
+            );
+            member_importers.extend(parsed_lockfile.importers);
+        }
+    }
+    let mut member_dependencies = merge_installed_copies(member_dependencies);
+    mark_direct_dependencies(&mut member_dependencies, &member_importers);
+    member_dependencies
+}
+
 fn main() {
     let ParsedArgs {
-        manager,
+        managers,
         root,
         output,
+        format,
+        workspaces,
+        scopes,
+        inline_license_text,
+        command,
     } = cli();
-    let manager = manager;
-    let root = root;
-    let output = output;
-    let dependency_lockfiles = handle_dependencies_files(manager, &root);
+    let dependency_lockfiles = handle_dependencies_files(&managers, &root);
+    let document_name = root
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("dependencies")
+        .to_string();
 
-    for lockfile in dependency_lockfiles.iter() {
+    let mut parsed_dependencies = Vec::new();
+    let mut importers = Vec::new();
+    for (manager, lockfile) in dependency_lockfiles.iter() {
         match lockfile.to_str() {
             Some(path) => {
-                let output_path = match output.to_str().unwrap().len() > 0 {
-                    true => output.to_str(),
-                    _ => Option::from("./dependencies.json"),
+                let parsed_lockfile = parse_lock_file(*manager, &root, path);
+                parsed_dependencies.extend(parsed_lockfile.packages);
+                importers.extend(parsed_lockfile.importers);
+            }
+            _ => eprint!("Something went wrong"),
+        }
+    }
+    // The same lockfile entries can be reached from several managers, e.g. a project with both
+    // a package-lock.json and a yarn.lock.
+    let mut parsed_dependencies = merge_installed_copies(parsed_dependencies);
+    if !scopes.is_empty() {
+        parsed_dependencies.retain(|pkg| scopes.contains(&pkg.scope()));
+    }
+    mark_direct_dependencies(&mut parsed_dependencies, &importers);
+    let members = select_members(&root, &workspaces);
+
+    if let Some(Command::Explain { package }) = command {
+        let member_paths: Vec<String> = members.iter().map(|member| member.path.clone()).collect();
+        let lines = explain(
+            &parsed_dependencies,
+            &importers,
+            &root,
+            &package,
+            &member_paths,
+        );
+        if lines.is_empty() {
+            eprintln!(
+                "No package named {package} in the dependencies of {:?}",
+                root
+            );
+            process::exit(1);
+        }
+        println!("{}", lines.join("\n"));
+        return;
+    }
+
+    let output_path = match !output.to_str().unwrap().is_empty() {
+        true => output.to_str(),
+        _ => Option::from("./dependencies.json"),
+    };
+
+    // The reports to write or check: the whole project, or each selected member.
+    let reports: Vec<(String, String, Vec<ParsedPackageJson>)> = match workspaces.is_empty() {
+        true => vec![(
+            document_name,
+            output_path.unwrap().to_string(),
+            parsed_dependencies,
+        )],
+        false => members
+            .iter()
+            .map(|member| {
+                let member_output = match workspaces.len() {
+                    1 => output_path.unwrap().to_string(),
+                    _ => member_output_path(output_path.unwrap(), &member.name),
                 };
+                let member_dependencies = member_dependencies(
+                    member,
+                    &parsed_dependencies,
+                    &importers,
+                    &managers,
+                    &scopes,
+                    &root,
+                );
+                (member.name.clone(), member_output, member_dependencies)
+            })
+            .collect(),
+    };
 
-                let parsed_dependencies = parse_lock_file(manager, &root, path);
-                let parsed_dependencies_json: serde_json::Map<String, Value> = parsed_dependencies
-                    .iter()
-                    .map(|pkg| {
-                        let pkg_value =
-                            serde_json::to_value(pkg).expect("Failed to serialize package");
-                        (pkg.name.clone(), pkg_value)
-                    })
-                    .collect();
-
-                let _ =
-                    write_node_dependencies_to_file(parsed_dependencies_json, output_path.unwrap());
+    if let Some(Command::Check { policy }) = command {
+        let policy = match policy {
+            Some(path) => match Policy::from_file(&path) {
+                Ok(policy) => policy,
+                Err(error) => {
+                    eprintln!("Failed to read the policy {:?}: {error}", path);
+                    process::exit(2);
+                }
+            },
+            None => Policy::default(),
+        };
+        let mut passes = true;
+        for (name, _, packages) in reports.iter() {
+            let report = policy.check(packages);
+            if !workspaces.is_empty() {
+                println!("{name}:");
             }
-            _ => eprint!("Something went wrong"),
+            println!("{}", report.lines().join("\n"));
+            passes &= report.passes();
+        }
+        if !passes {
+            process::exit(1);
+        }
+        return;
+    }
+
+    for (name, report_output, mut packages) in reports {
+        // The notices and pages are made of the texts, whether or not the option is set.
+        if !inline_license_text
+            && !matches!(
+                format,
+                OutputFormat::Notice
+                    | OutputFormat::Markdown
+                    | OutputFormat::Html
+                    | OutputFormat::AndroidOssLicenses
+                    | OutputFormat::AndroidLicensesXml
+            )
+        {
+            for package in packages.iter_mut() {
+                package.license_text.clear();
+                package.notice_text.clear();
+            }
+        }
+        if let Err(error) = write_report(format, &packages, &name, &report_output) {
+            eprintln!("Failed to write {report_output}: {error}");
         }
     }
 }
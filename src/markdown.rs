@@ -0,0 +1,160 @@
+use crate::notice::{license_groups, LicenseGroup};
+use crate::parser::ParsedPackageJson;
+use crate::write::escape_xml;
+
+// Escapes what Markdown would read as markup: backslashes, code spans, emphasis, links, HTML
+// tags, the `|` ending table cells, and a leading `#`, `-`, `+` or `>` starting a heading, list
+// or quote. Line breaks, which end cells and headings, become spaces.
+fn escape_markdown(value: &str) -> String {
+    let mut escaped = String::new();
+    for (index, c) in value.chars().enumerate() {
+        match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '|' | '<' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '#' | '-' | '+' | '>' if index == 0 => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\r' | '\n' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Link destinations end at a space or an unbalanced parenthesis.
+fn link_destination(url: &str) -> String {
+    url.replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29")
+}
+
+/// The repository of the package, its homepage when it has none, empty unless it is a web
+/// page a link can point to.
+pub(crate) fn package_url(package: &ParsedPackageJson) -> &str {
+    [&package.repository_url, &package.homepage]
+        .into_iter()
+        .find(|url| url.starts_with("https://") || url.starts_with("http://"))
+        .map(String::as_str)
+        .unwrap_or_default()
+}
+
+// A fence longer than any run of backticks in the text, so it cannot close the block early.
+fn fence(text: &str) -> String {
+    let longest_run = text
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    "`".repeat(longest_run.max(2) + 1)
+}
+
+/// The packages of the groups with the index of their group, in the order of their names.
+pub(crate) fn package_groups<'a>(
+    groups: &[LicenseGroup<'a>],
+) -> Vec<(&'a ParsedPackageJson, usize)> {
+    let mut packages: Vec<(&ParsedPackageJson, usize)> = groups
+        .iter()
+        .enumerate()
+        .flat_map(|(index, group)| group.packages.iter().map(move |package| (*package, index)))
+        .collect();
+    packages.sort_by_key(|(package, _)| (package.key(), package.ecosystem.as_str()));
+    packages
+}
+
+/// The copyright lines of the packages, each once.
+pub(crate) fn group_copyrights<'a>(packages: &[&'a ParsedPackageJson]) -> Vec<&'a String> {
+    let mut copyrights = Vec::new();
+    for copyright in packages
+        .iter()
+        .flat_map(|package| package.copyrights.iter())
+    {
+        if !copyrights.contains(&copyright) {
+            copyrights.push(copyright);
+        }
+    }
+    copyrights
+}
+
+/// Builds a Markdown page: a table of the packages, then a collapsible section for each license
+/// text with the packages under it.
+pub(crate) fn to_markdown(packages: &[ParsedPackageJson], document_name: &str) -> String {
+    let groups = license_groups(packages);
+    let mut lines = vec![
+        "# Open source licenses".to_string(),
+        String::new(),
+        format!("{document_name} uses the following open source packages."),
+        String::new(),
+        "| Package | Version | Ecosystem | License |".to_string(),
+        "| --- | --- | --- | --- |".to_string(),
+    ];
+    for (package, index) in package_groups(&groups) {
+        let name = match package_url(package) {
+            "" => escape_markdown(&package.name),
+            url => format!(
+                "[{}]({})",
+                escape_markdown(&package.name),
+                link_destination(url)
+            ),
+        };
+        lines.push(format!(
+            "| {name} | {} | {} | {} |",
+            escape_markdown(&package.version),
+            package.ecosystem.as_str(),
+            escape_markdown(&groups[index].name)
+        ));
+    }
+
+    lines.extend([String::new(), "## Licenses".to_string()]);
+    for group in &groups {
+        let package_keys: Vec<String> =
+            group.packages.iter().map(|package| package.key()).collect();
+        lines.extend([
+            String::new(),
+            format!("### {}", escape_markdown(&group.name)),
+            String::new(),
+            "<details>".to_string(),
+            format!(
+                "<summary>{}</summary>",
+                escape_xml(&package_keys.join(", "))
+            ),
+            String::new(),
+        ]);
+        let copyrights = group_copyrights(&group.packages);
+        lines.extend(
+            copyrights
+                .iter()
+                .map(|copyright| format!("- {}", escape_markdown(copyright))),
+        );
+        if !copyrights.is_empty() {
+            lines.push(String::new());
+        }
+        for package in group
+            .packages
+            .iter()
+            .filter(|package| !package.notice_text.is_empty())
+        {
+            let fence = fence(&package.notice_text);
+            lines.extend([
+                format!("NOTICE of {}:", escape_markdown(&package.key())),
+                String::new(),
+                format!("{fence}text"),
+                package.notice_text.trim_end().to_string(),
+                fence,
+                String::new(),
+            ]);
+        }
+        match group.text.is_empty() {
+            true => lines.push("No license text was found in these packages.".to_string()),
+            false => {
+                let fence = fence(&group.text);
+                lines.extend([format!("{fence}text"), group.text.clone(), fence]);
+            }
+        }
+        lines.extend([String::new(), "</details>".to_string()]);
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
@@ -0,0 +1,426 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use roxmltree::{Document, Node};
+
+// Guards against parent POM cycles.
+const MAX_PARENT_DEPTH: usize = 16;
+
+#[derive(Debug, Default, Clone)]
+pub(crate) struct PomMetadata {
+    pub(crate) name: Option<String>,
+    pub(crate) description: Option<String>,
+    pub(crate) url: Option<String>,
+    pub(crate) scm_url: Option<String>,
+    pub(crate) licenses: Vec<PomLicense>,
+    pub(crate) developers: Vec<String>,
+    // Artifacts shipped with this one, test, provided and optional ones left out.
+    pub(crate) dependencies: Vec<PomDependency>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct PomLicense {
+    pub(crate) name: String,
+    pub(crate) url: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct PomDependency {
+    pub(crate) group: String,
+    pub(crate) artifact: String,
+    // Empty when neither the POM nor its `<dependencyManagement>` sets it.
+    pub(crate) version: String,
+}
+
+// Scopes of the `<dependencies>` an app ships.
+const SHIPPED_SCOPES: [&str; 2] = ["compile", "runtime"];
+
+struct Pom {
+    metadata: PomMetadata,
+    parent: Option<(String, String, String)>,
+    properties: HashMap<String, String>,
+    // Versions `<dependencyManagement>` sets by `group:artifact`.
+    managed_versions: HashMap<String, String>,
+}
+
+/// Local artifact repositories a machine that already built the app has populated.
+pub(crate) struct MavenRepositories {
+    gradle_cache: Option<PathBuf>,
+    maven_local: Option<PathBuf>,
+}
+
+impl MavenRepositories {
+    /// Uses `GRADLE_USER_HOME` (or `~/.gradle`) and `~/.m2/repository`.
+    pub(crate) fn from_environment() -> MavenRepositories {
+        let home = env::var_os("HOME")
+            .or_else(|| env::var_os("USERPROFILE"))
+            .map(PathBuf::from);
+        let gradle_home = env::var_os("GRADLE_USER_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|home| home.join(".gradle")));
+
+        MavenRepositories {
+            gradle_cache: gradle_home
+                .map(|gradle_home| gradle_home.join("caches/modules-2/files-2.1"))
+                .filter(|path| path.is_dir()),
+            maven_local: home
+                .map(|home| home.join(".m2/repository"))
+                .filter(|path| path.is_dir()),
+        }
+    }
+
+    /// Returns the versions of an artifact present in the local repositories.
+    pub(crate) fn cached_versions(&self, group: &str, artifact: &str) -> Vec<String> {
+        let mut versions: Vec<String> = self
+            .artifact_directories(group, artifact)
+            .iter()
+            .filter_map(|directory| fs::read_dir(directory).ok())
+            .flat_map(|entries| entries.flatten())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect();
+        versions.sort();
+        versions.dedup();
+        versions
+    }
+
+    fn artifact_directories(&self, group: &str, artifact: &str) -> Vec<PathBuf> {
+        let mut directories = Vec::new();
+        if let Some(gradle_cache) = &self.gradle_cache {
+            directories.push(gradle_cache.join(group).join(artifact));
+        }
+        if let Some(maven_local) = &self.maven_local {
+            directories.push(maven_local.join(group.replace('.', "/")).join(artifact));
+        }
+        directories
+    }
+
+    fn find_file(
+        &self,
+        group: &str,
+        artifact: &str,
+        version: &str,
+        extension: &str,
+    ) -> Option<PathBuf> {
+        let file_name = format!("{artifact}-{version}.{extension}");
+
+        self.artifact_directories(group, artifact)
+            .into_iter()
+            .map(|directory| directory.join(version))
+            .find_map(|version_directory| {
+                // The Gradle cache stores each file under a directory named after its sha1.
+                let maven_layout = version_directory.join(&file_name);
+                if maven_layout.is_file() {
+                    return Some(maven_layout);
+                }
+                fs::read_dir(&version_directory)
+                    .ok()?
+                    .flatten()
+                    .map(|entry| entry.path().join(&file_name))
+                    .find(|path| path.is_file())
+            })
+    }
+
+    fn find_pom(&self, group: &str, artifact: &str, version: &str) -> Option<PathBuf> {
+        self.find_file(group, artifact, version, "pom")
+    }
+
+    /// Returns the cached jar or aar of an artifact, whose `META-INF` may hold its license.
+    pub(crate) fn find_archive(
+        &self,
+        group: &str,
+        artifact: &str,
+        version: &str,
+    ) -> Option<PathBuf> {
+        ["jar", "aar"]
+            .iter()
+            .find_map(|extension| self.find_file(group, artifact, version, extension))
+    }
+
+    /// Reads the POM of an artifact and merges in what it inherits from its parents.
+    pub(crate) fn resolve(
+        &self,
+        group: &str,
+        artifact: &str,
+        version: &str,
+    ) -> Option<PomMetadata> {
+        let mut pom = read_pom(&self.find_pom(group, artifact, version)?)?;
+        let mut metadata = pom.metadata.clone();
+        let mut managed_versions = pom.managed_versions.clone();
+        let mut properties = pom.properties;
+        properties.insert("project.version".to_string(), version.to_string());
+        properties.insert("project.groupId".to_string(), group.to_string());
+        properties.insert("project.artifactId".to_string(), artifact.to_string());
+
+        let mut depth = 0;
+        while let Some((parent_group, parent_artifact, parent_version)) = pom.parent.take() {
+            depth += 1;
+            if depth > MAX_PARENT_DEPTH {
+                break;
+            }
+            let Some(parent_path) = self.find_pom(&parent_group, &parent_artifact, &parent_version)
+            else {
+                break;
+            };
+            let Some(parent) = read_pom(&parent_path) else {
+                break;
+            };
+
+            properties
+                .entry("project.parent.version".to_string())
+                .or_insert(parent_version);
+            for (key, value) in &parent.properties {
+                properties.entry(key.clone()).or_insert(value.clone());
+            }
+            inherit(&mut metadata, &parent.metadata);
+            for (key, value) in &parent.managed_versions {
+                managed_versions.entry(key.clone()).or_insert(value.clone());
+            }
+            pom = parent;
+        }
+
+        for dependency in metadata.dependencies.iter_mut() {
+            if dependency.version.is_empty() {
+                let key = format!("{}:{}", dependency.group, dependency.artifact);
+                dependency.version = managed_versions.get(&key).cloned().unwrap_or_default();
+            }
+        }
+        Some(interpolate(metadata, &properties))
+    }
+}
+
+fn inherit(metadata: &mut PomMetadata, parent: &PomMetadata) {
+    if metadata.url.is_none() {
+        metadata.url = parent.url.clone();
+    }
+    if metadata.scm_url.is_none() {
+        metadata.scm_url = parent.scm_url.clone();
+    }
+    if metadata.description.is_none() {
+        metadata.description = parent.description.clone();
+    }
+    if metadata.licenses.is_empty() {
+        metadata.licenses = parent.licenses.clone();
+    }
+    if metadata.developers.is_empty() {
+        metadata.developers = parent.developers.clone();
+    }
+    // Children depend on what their parent depends on.
+    for dependency in &parent.dependencies {
+        if !metadata
+            .dependencies
+            .iter()
+            .any(|own| own.group == dependency.group && own.artifact == dependency.artifact)
+        {
+            metadata.dependencies.push(dependency.clone());
+        }
+    }
+}
+
+/// Replaces `${property}` placeholders, leaving unknown ones untouched.
+fn substitute(value: &str, properties: &HashMap<String, String>) -> String {
+    let mut substituted = String::new();
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        substituted.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        let key = &rest[2..end];
+        let key = key
+            .strip_prefix("pom.")
+            .map_or(key.to_string(), |key| format!("project.{key}"));
+        match properties.get(&key) {
+            Some(value) => substituted.push_str(value),
+            None => substituted.push_str(&rest[..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    substituted.push_str(rest);
+    substituted
+}
+
+fn interpolate(metadata: PomMetadata, properties: &HashMap<String, String>) -> PomMetadata {
+    let substitute_option =
+        |value: Option<String>| value.map(|value| substitute(&value, properties));
+
+    PomMetadata {
+        name: substitute_option(metadata.name),
+        description: substitute_option(metadata.description),
+        url: substitute_option(metadata.url),
+        scm_url: substitute_option(metadata.scm_url),
+        licenses: metadata
+            .licenses
+            .into_iter()
+            .map(|license| PomLicense {
+                name: substitute(&license.name, properties),
+                url: substitute_option(license.url),
+            })
+            .collect(),
+        developers: metadata
+            .developers
+            .iter()
+            .map(|developer| substitute(developer, properties))
+            .collect(),
+        dependencies: metadata
+            .dependencies
+            .into_iter()
+            .map(|dependency| PomDependency {
+                group: substitute(&dependency.group, properties),
+                artifact: substitute(&dependency.artifact, properties),
+                version: substitute(&dependency.version, properties),
+            })
+            .collect(),
+    }
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children()
+        .find(|child| child.is_element() && child.tag_name().name() == name)
+}
+
+fn child_text(node: Node, name: &str) -> Option<String> {
+    child(node, name)
+        .and_then(|child| child.text())
+        .map(|text| text.split_whitespace().collect::<Vec<&str>>().join(" "))
+        .filter(|text| !text.is_empty())
+}
+
+/// The elements of a `<dependencies>`, with the scope and optional flag of each.
+fn dependency_elements<'a, 'input>(
+    dependencies: Option<Node<'a, 'input>>,
+) -> impl Iterator<Item = (PomDependency, String, bool)> + 'a {
+    dependencies
+        .into_iter()
+        .flat_map(|dependencies| dependencies.children())
+        .filter(|dependency| dependency.is_element())
+        .filter_map(|dependency| {
+            let pom_dependency = PomDependency {
+                group: child_text(dependency, "groupId")?,
+                artifact: child_text(dependency, "artifactId")?,
+                version: child_text(dependency, "version").unwrap_or_default(),
+            };
+            let scope = child_text(dependency, "scope").unwrap_or("compile".to_string());
+            let optional = child_text(dependency, "optional").as_deref() == Some("true");
+            Some((pom_dependency, scope, optional))
+        })
+}
+
+fn read_pom(path: &Path) -> Option<Pom> {
+    let content = fs::read_to_string(path).ok()?;
+    let document = match Document::parse(&content) {
+        Ok(document) => document,
+        Err(error) => {
+            eprintln!("Error parsing {:?}: {error}", path);
+            return None;
+        }
+    };
+    let project = document.root_element();
+
+    let parent = child(project, "parent").and_then(|parent| {
+        Some((
+            child_text(parent, "groupId")?,
+            child_text(parent, "artifactId")?,
+            child_text(parent, "version")?,
+        ))
+    });
+
+    let properties = child(project, "properties")
+        .map(|properties| {
+            properties
+                .children()
+                .filter(|property| property.is_element())
+                .map(|property| {
+                    (
+                        property.tag_name().name().to_string(),
+                        property.text().unwrap_or_default().trim().to_string(),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let licenses = child(project, "licenses")
+        .map(|licenses| {
+            licenses
+                .children()
+                .filter(|license| license.is_element())
+                .filter_map(|license| {
+                    let url = child_text(license, "url");
+                    Some(PomLicense {
+                        name: child_text(license, "name").or(url.clone())?,
+                        url,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let developers: Vec<String> = child(project, "developers")
+        .map(|developers| {
+            developers
+                .children()
+                .filter(|developer| developer.is_element())
+                .filter_map(|developer| {
+                    child_text(developer, "name")
+                        .or_else(|| child_text(developer, "organization"))
+                        .or_else(|| child_text(developer, "id"))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let dependencies = dependency_elements(child(project, "dependencies"))
+        .filter(|(_, scope, optional)| SHIPPED_SCOPES.contains(&scope.as_str()) && !optional)
+        .map(|(dependency, _, _)| dependency)
+        .collect();
+    let managed_versions = dependency_elements(
+        child(project, "dependencyManagement")
+            .and_then(|management| child(management, "dependencies")),
+    )
+    .filter(|(dependency, _, _)| !dependency.version.is_empty())
+    .map(|(dependency, _, _)| {
+        (
+            format!("{}:{}", dependency.group, dependency.artifact),
+            dependency.version,
+        )
+    })
+    .collect();
+
+    let scm_url = child(project, "scm").and_then(|scm| {
+        child_text(scm, "url").or_else(|| {
+            child_text(scm, "connection").map(|connection| {
+                connection
+                    .trim_start_matches("scm:")
+                    .trim_start_matches("git:")
+                    .to_string()
+            })
+        })
+    });
+
+    Some(Pom {
+        metadata: PomMetadata {
+            name: child_text(project, "name"),
+            description: child_text(project, "description"),
+            url: child_text(project, "url"),
+            scm_url,
+            licenses,
+            developers: if developers.is_empty() {
+                child(project, "organization")
+                    .and_then(|organization| child_text(organization, "name"))
+                    .into_iter()
+                    .collect()
+            } else {
+                developers
+            },
+            dependencies,
+        },
+        parent,
+        properties,
+        managed_versions,
+    })
+}
@@ -0,0 +1,117 @@
+use std::collections::BTreeMap;
+
+use crate::parser::ParsedPackageJson;
+
+const SEPARATOR: &str =
+    "================================================================================";
+
+/// The license of a package as the heading of its group.
+fn license_name(package: &ParsedPackageJson) -> String {
+    match (package.known_license(), package.license.trim()) {
+        (Some(expression), _) => expression.to_string(),
+        (None, "") => "Unknown license".to_string(),
+        (None, license) => license.to_string(),
+    }
+}
+
+/// The license text without the copyright lines heading it, listed with the package instead,
+/// so packages under the same license but different holders share one text. Lines further
+/// down are part of the license even when they read like a copyright line.
+fn license_body(package: &ParsedPackageJson) -> String {
+    let lines: Vec<&str> = package
+        .license_text
+        .lines()
+        .map(str::trim_end)
+        .skip_while(|line| line.is_empty() || package.copyrights.contains(&text_key(line)))
+        .collect();
+    lines.join("\n").trim_end().to_string()
+}
+
+// Compared without whitespace, files rewrapped or indented differently are the same text.
+fn text_key(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Packages sharing a license text.
+pub(crate) struct LicenseGroup<'a> {
+    // The license the packages declare, or the one detected from the text.
+    pub(crate) name: String,
+    // Empty when no license file was found in the packages.
+    pub(crate) text: String,
+    pub(crate) packages: Vec<&'a ParsedPackageJson>,
+}
+
+/// Groups the packages by license name then text, those without a license file by their name
+/// alone, in the order of their names.
+pub(crate) fn license_groups(packages: &[ParsedPackageJson]) -> Vec<LicenseGroup<'_>> {
+    let mut sorted: Vec<&ParsedPackageJson> = packages.iter().collect();
+    sorted.sort_by_key(|package| (package.key(), package.ecosystem.as_str()));
+
+    let mut groups: BTreeMap<(String, String), LicenseGroup> = BTreeMap::new();
+    for package in sorted {
+        let text = license_body(package);
+        let name = license_name(package);
+        groups
+            .entry((name.clone(), text_key(&text)))
+            .or_insert_with(|| LicenseGroup {
+                name,
+                text,
+                packages: Vec::new(),
+            })
+            .packages
+            .push(package);
+    }
+    groups.into_values().collect()
+}
+
+/// Builds the third-party notices: for each license text, the packages under it with their
+/// copyright lines and NOTICE files, then the text itself.
+pub(crate) fn to_text(packages: &[ParsedPackageJson]) -> String {
+    let mut lines = vec![
+        "THIRD-PARTY SOFTWARE NOTICES".to_string(),
+        String::new(),
+        "This software includes the following third-party packages, listed by license.".to_string(),
+    ];
+    for LicenseGroup {
+        name,
+        text,
+        packages: group,
+    } in license_groups(packages)
+    {
+        lines.extend([
+            String::new(),
+            SEPARATOR.to_string(),
+            name,
+            SEPARATOR.to_string(),
+        ]);
+        lines.push(String::new());
+        for package in &group {
+            match package.repository_url.is_empty() {
+                true => lines.push(package.key()),
+                false => lines.push(format!("{} ({})", package.key(), package.repository_url)),
+            }
+            lines.extend(
+                package
+                    .copyrights
+                    .iter()
+                    .map(|copyright| format!("  {copyright}")),
+            );
+        }
+        for package in group
+            .iter()
+            .filter(|package| !package.notice_text.is_empty())
+        {
+            lines.push(String::new());
+            lines.push(format!("NOTICE of {}:", package.key()));
+            lines.push(String::new());
+            lines.push(package.notice_text.trim_end().to_string());
+        }
+        lines.push(String::new());
+        match text.is_empty() {
+            true => lines.push("No license text was found in these packages.".to_string()),
+            false => lines.push(text),
+        }
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
@@ -1,15 +1,25 @@
-use std::collections::{HashMap, HashSet};
-use std::fs::File;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::{self, File};
 use std::io;
 use std::io::{BufReader, Read};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use base64::prelude::{Engine, BASE64_STANDARD};
+use clap::ValueEnum;
 use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
 
 use crate::cli::ManagersArgs;
 use crate::format_file_path;
+use crate::gradle::{self, GradleDependency, VersionCatalog};
+use crate::license::LicenseExpression;
+use crate::license_text::{detect_license, is_copyright_line, DetectedLicense};
+use crate::maven::MavenRepositories;
+use crate::pnp::{self, PnpManifest};
+use crate::workspace::{workspace_members, WorkspaceMember};
 
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 struct PackageLockJson {
     name: Option<String>,
     version: Option<String>,
@@ -19,149 +29,1744 @@ struct PackageLockJson {
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 struct PackageLockDevDependencies {
     name: Option<String>,
     version: Option<String>,
+    resolved: Option<String>,
+    integrity: Option<String>,
+    license: Option<Value>,
+    #[serde(default)]
+    dev: bool,
+    #[serde(default)]
+    optional: bool,
+    #[serde(default)]
+    dev_optional: bool,
+    #[serde(default)]
+    peer: bool,
+    #[serde(default)]
+    link: bool,
     dependencies: Option<HashMap<String, String>>,
     dev_dependencies: Option<HashMap<String, String>>,
+    optional_dependencies: Option<HashMap<String, String>>,
+    peer_dependencies: Option<HashMap<String, String>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// A package installed under `node_modules`, with what the lockfile already knows about it.
+#[derive(Debug, Default)]
+struct NodeModule {
+    path: String,
+    name: Option<String>,
+    version: Option<String>,
+    license: Option<String>,
+    resolved: Option<String>,
+    integrity: Option<String>,
+    // `name@version` of the packages it depends on.
+    dependencies: Vec<String>,
+    // The ones of `dependencies` it can be installed without.
+    optional_dependencies: Vec<String>,
+    // When the lockfile records it, otherwise found from what the projects depend on.
+    scope: Option<Scope>,
+}
+
+/// An entry of a Yarn Berry (v2+) lockfile. Versions and ranges are kept as YAML scalars
+/// since unquoted ones like `2` or `1.0` read as numbers.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YarnBerryEntry {
+    version: Option<serde_yaml::Value>,
+    resolution: Option<String>,
+    #[serde(default)]
+    dependencies: HashMap<String, serde_yaml::Value>,
+    language_name: Option<String>,
+    link_type: Option<String>,
+    // `name: {optional: true}` for optional dependencies.
+    #[serde(default)]
+    dependencies_meta: HashMap<String, serde_yaml::Value>,
+}
+
+/// A pnpm-lock.yaml. v5 keys packages `/name/version_peers`, v6 `/name@version(peers)` and
+/// v9 `name@version`, with the dependencies of each peer variant moved to `snapshots`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct PnpmLockFile {
-    dependencies: HashMap<String, Option<PnpmLockSpecifier>>,
-    dev_dependencies: HashMap<String, Option<PnpmLockSpecifier>>,
-    packages: HashMap<String, Option<PnpmLockSpecifier>>,
+    lockfile_version: Option<serde_yaml::Value>,
+    // Workspaces list each project here, single projects keep their dependencies top level.
+    #[serde(default)]
+    importers: HashMap<String, PnpmLockImporter>,
+    #[serde(flatten)]
+    root_importer: PnpmLockImporter,
+    #[serde(default)]
+    packages: HashMap<String, PnpmLockPackage>,
+    #[serde(default)]
+    snapshots: HashMap<String, PnpmLockPackage>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct PnpmLockSpecifier {
-    specifier: Option<String>,
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PnpmLockPackage {
+    resolution: Option<PnpmLockResolution>,
+    // Only set for packages not coming from the registry, e.g. tarballs and git repositories.
+    name: Option<String>,
     version: Option<String>,
+    #[serde(default)]
+    dependencies: HashMap<String, serde_yaml::Value>,
+    #[serde(default)]
+    optional_dependencies: HashMap<String, serde_yaml::Value>,
+    dev: Option<bool>,
+    #[serde(default)]
+    optional: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// The dependencies of a project, `name: version` in v5 and `name: {specifier, version}` since.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PnpmLockImporter {
+    #[serde(default)]
+    dependencies: HashMap<String, serde_yaml::Value>,
+    #[serde(default)]
+    dev_dependencies: HashMap<String, serde_yaml::Value>,
+    #[serde(default)]
+    optional_dependencies: HashMap<String, serde_yaml::Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PnpmLockResolution {
+    integrity: Option<String>,
+    tarball: Option<String>,
+    repo: Option<String>,
+    commit: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PodfileLock {
+    #[serde(rename = "PODS", default)]
+    pods: Vec<PodfileLockPod>,
+    #[serde(rename = "EXTERNAL SOURCES", default)]
+    external_sources: HashMap<String, HashMap<String, String>>,
+    // The pods the Podfile asks for, e.g. `React-Core (from `../node_modules/react-native/`)`.
+    #[serde(rename = "DEPENDENCIES", default)]
+    dependencies: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum PodfileLockPod {
+    Pod(String),
+    PodWithDependencies(HashMap<String, Vec<String>>),
+}
+
+#[derive(Debug)]
+struct PodLockEntry {
+    name: String,
+    version: String,
+    podspec: Option<String>,
+    path: Option<String>,
+    // `name@version` of the pods it depends on.
+    dependencies: Vec<String>,
+    // Listed in the Podfile rather than only needed by other pods.
+    direct: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct PodspecJson {
+    name: Option<String>,
+    version: Option<String>,
+    summary: Option<String>,
+    description: Option<String>,
+    homepage: Option<String>,
+    license: Option<PodspecLicense>,
+    #[serde(alias = "author")]
+    authors: Option<PodspecAuthors>,
+    source: Option<HashMap<String, Value>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum PodspecLicense {
+    Type(String),
+    Detailed {
+        #[serde(rename = "type")]
+        license_type: Option<String>,
+        file: Option<String>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum PodspecAuthors {
+    Name(String),
+    Names(Vec<String>),
+    NamesWithEmail(HashMap<String, Option<String>>),
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
 struct PackageJson {
     name: Option<String>,
+    version: Option<String>,
     description: Option<String>,
     repository: Option<Repository>,
-    author: Option<String>,
-    license: Option<String>,
+    author: Option<Person>,
+    contributors: Option<People>,
+    maintainers: Option<People>,
+    license: Option<License>,
+    licenses: Option<Licenses>,
+    homepage: Option<String>,
+    dependencies: Option<Value>,
+    #[serde(rename = "devDependencies")]
+    dev_dependencies: Option<Value>,
+    #[serde(rename = "optionalDependencies")]
+    optional_dependencies: Option<Value>,
+    #[serde(rename = "packageManager")]
+    package_manager: Option<String>,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
-struct Repository {
-    url: Option<String>,
+// package.json fields accept several shapes, `Other` keeps an unexpected one from failing the
+// whole file.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Person {
+    Name(String),
+    Detailed {
+        name: Option<String>,
+        email: Option<String>,
+        url: Option<String>,
+    },
+    Other(Value),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum People {
+    Many(Vec<Person>),
+    One(Person),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum License {
+    Id(String),
+    Detailed {
+        #[serde(rename = "type")]
+        license_type: Option<String>,
+        url: Option<String>,
+    },
+    Other(Value),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Licenses {
+    Many(Vec<License>),
+    One(License),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Repository {
+    Shorthand(String),
+    Detailed { url: Option<String> },
+    Other(Value),
+}
+
+impl Person {
+    /// Formats a person the way npm's `author` string does: `Name <email> (url)`.
+    fn format(&self) -> Option<String> {
+        match self {
+            Person::Name(name) => Some(name.trim().to_string()).filter(|name| !name.is_empty()),
+            Person::Detailed { name, email, url } => {
+                let mut formatted = name.clone().unwrap_or_default();
+                if let Some(email) = email.as_ref().filter(|email| !email.is_empty()) {
+                    formatted = format!("{formatted} <{email}>");
+                }
+                if let Some(url) = url.as_ref().filter(|url| !url.is_empty()) {
+                    formatted = format!("{formatted} ({url})");
+                }
+                Some(formatted.trim().to_string()).filter(|formatted| !formatted.is_empty())
+            }
+            Person::Other(_) => None,
+        }
+    }
+}
+
+impl People {
+    fn format(&self) -> Vec<String> {
+        match self {
+            People::Many(people) => people.iter().filter_map(Person::format).collect(),
+            People::One(person) => person.format().into_iter().collect(),
+        }
+    }
+}
+
+impl License {
+    fn id(&self) -> Option<String> {
+        match self {
+            License::Id(id) => Some(id.clone()),
+            License::Detailed { license_type, url } => license_type.clone().or(url.clone()),
+            License::Other(_) => None,
+        }
+    }
+}
+
+impl PackageJson {
+    /// Returns the `(name, range, scope)` of every dependency the package declares.
+    fn dependency_ranges(&self) -> Vec<(String, String, Scope)> {
+        [
+            (&self.dependencies, Scope::Production),
+            (&self.optional_dependencies, Scope::Optional),
+            (&self.dev_dependencies, Scope::Development),
+        ]
+        .into_iter()
+        .filter_map(|(dependencies, scope)| Some((dependencies.as_ref()?.as_object()?, scope)))
+        .flat_map(|(dependencies, scope)| {
+            dependencies.iter().filter_map(move |(name, range)| {
+                Some((name.clone(), range.as_str()?.to_string(), scope))
+            })
+        })
+        .collect()
+    }
+
+    /// The scope a project depends on `name` with, `dependencies` winning over the other
+    /// fields.
+    fn dependency_scope(&self, name: &str) -> Scope {
+        self.dependency_ranges()
+            .into_iter()
+            .filter(|(dependency, _, _)| dependency == name)
+            .map(|(_, _, scope)| scope)
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// The declared license, falling back to the deprecated `licenses` array whose entries
+    /// are alternatives.
+    fn license_id(&self) -> Option<String> {
+        if let Some(license) = self.license.as_ref().and_then(License::id) {
+            return Some(license);
+        }
+
+        let licenses: Vec<String> = match self.licenses.as_ref()? {
+            Licenses::Many(licenses) => licenses.iter().filter_map(License::id).collect(),
+            Licenses::One(license) => license.id().into_iter().collect(),
+        };
+        match licenses.len() {
+            0 => None,
+            1 => licenses.into_iter().next(),
+            _ => Some(format!("({})", licenses.join(" OR "))),
+        }
+    }
+
+    fn author_name(&self) -> Option<String> {
+        self.author.as_ref().and_then(Person::format)
+    }
+
+    fn repository_url(&self) -> Option<String> {
+        let url = match self.repository.as_ref()? {
+            Repository::Shorthand(url) => url,
+            Repository::Detailed { url } => url.as_ref()?,
+            Repository::Other(_) => return None,
+        };
+        Some(normalize_repository_url(url))
+    }
+
+    fn contributors(&self) -> Vec<String> {
+        self.contributors
+            .as_ref()
+            .map(People::format)
+            .unwrap_or_default()
+    }
+
+    fn maintainers(&self) -> Vec<String> {
+        self.maintainers
+            .as_ref()
+            .map(People::format)
+            .unwrap_or_default()
+    }
+}
+
+/// Expands npm repository shorthands (`github:user/repo`, `gitlab:user/repo`,
+/// `bitbucket:user/repo`, `gist:id` and bare `user/repo`) into URLs.
+fn normalize_repository_url(url: &str) -> String {
+    let url = url.trim();
+    let hosts = [
+        ("github:", "https://github.com/"),
+        ("gitlab:", "https://gitlab.com/"),
+        ("bitbucket:", "https://bitbucket.org/"),
+        ("gist:", "https://gist.github.com/"),
+    ];
+
+    for (prefix, host) in hosts {
+        if let Some(path) = url.strip_prefix(prefix) {
+            return format!("{host}{path}");
+        }
+    }
+
+    let is_user_repo = !url.contains(':')
+        && !url.starts_with('.')
+        && url.matches('/').count() == 1
+        && !url.starts_with('/')
+        && !url.ends_with('/');
+    if is_user_repo {
+        return format!("https://github.com/{url}");
+    }
+    url.to_string()
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ecosystem {
+    #[default]
+    Npm,
+    CocoaPods,
+    Maven,
+}
+
+impl Ecosystem {
+    /// The name used for the ecosystem in reports and package URLs.
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Ecosystem::Npm => "npm",
+            Ecosystem::CocoaPods => "cocoapods",
+            Ecosystem::Maven => "maven",
+        }
+    }
+}
+
+/// Why a package is installed. Variants are ordered from the most to the least likely to ship
+/// with the app.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    // Needed at runtime by the app.
+    #[default]
+    Production,
+    // Only needed through optional dependencies, e.g. platform specific binaries.
+    Optional,
+    // Only needed to build or test the app.
+    Development,
+}
+
+impl Scope {
+    /// The name used for the scope in reports.
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Scope::Production => "production",
+            Scope::Optional => "optional",
+            Scope::Development => "development",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ParsedPackageJson {
     pub(crate) name: String,
-    description: String,
-    repository_url: String,
-    author: String,
-    license: String,
-    license_url: String,
+    pub(crate) version: String,
+    pub(crate) ecosystem: Ecosystem,
+    pub(crate) paths: Vec<String>,
+    pub(crate) resolved: String,
+    pub(crate) integrity: String,
+    pub(crate) description: String,
+    pub(crate) repository_url: String,
+    pub(crate) homepage: String,
+    pub(crate) author: String,
+    pub(crate) contributors: Vec<String>,
+    pub(crate) maintainers: Vec<String>,
+    pub(crate) license: String,
+    // `license` read as an SPDX expression, `None` when it names no known license.
+    pub(crate) license_expression: Option<LicenseExpression>,
+    // Identified from the license file when `license` names no known license.
+    pub(crate) detected_license: Option<DetectedLicense>,
+    pub(crate) license_url: String,
+    // Where the package metadata says the license can be read, e.g. a POM `<license><url>`.
+    pub(crate) declared_license_url: String,
+    // Contents of the license file, only written to reports asked to inline them.
+    #[serde(skip_serializing_if = "String::is_empty", default)]
+    pub(crate) license_text: String,
+    pub(crate) notice_url: String,
+    #[serde(skip_serializing_if = "String::is_empty", default)]
+    pub(crate) notice_text: String,
+    // Copyright statements found in the license and NOTICE files.
+    pub(crate) copyrights: Vec<String>,
+    pub(crate) dependencies: Vec<String>,
+    // `None` when the lockfile does not tell, until `merge_installed_copies` settles it.
+    pub(crate) scope: Option<Scope>,
+    // Depended on by a project rather than only by other packages.
+    pub(crate) direct: bool,
+}
+
+/// A project of the repository, the root one, a workspace member or a native app, with the
+/// packages it depends on directly.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Importer {
+    // Relative to the project root, empty for the root project.
+    pub(crate) path: String,
+    pub(crate) ecosystem: Ecosystem,
+    // `name@version` of the packages it depends on, with the field declaring them.
+    pub(crate) dependencies: Vec<(String, Scope)>,
+    // Paths of the workspace members it depends on.
+    pub(crate) workspace_dependencies: Vec<String>,
+}
+
+/// The packages of a node lockfile and the projects installing them.
+#[derive(Debug, Default)]
+struct NodeLockfile {
+    modules: Vec<NodeModule>,
+    importers: Vec<Importer>,
+}
+
+/// What a dependency file resolves to: the packages to report and, for node lockfiles, the
+/// projects depending on them.
+#[derive(Debug, Default)]
+pub(crate) struct ParsedLockfile {
+    pub(crate) packages: Vec<ParsedPackageJson>,
+    pub(crate) importers: Vec<Importer>,
 }
 
 struct DependencyFile;
 
 trait Parser {
-    fn parse_package_lock(lockfile_path: &str) -> Result<Vec<String>, Box<dyn std::error::Error>>;
-    fn parse_yarn_lock(lockfile_path: &str) -> Result<Vec<String>, Box<dyn std::error::Error>>;
+    fn parse_package_lock(lockfile_path: &str) -> Result<NodeLockfile, Box<dyn std::error::Error>>;
+    fn parse_yarn_lock(lockfile_path: &str) -> Result<NodeLockfile, Box<dyn std::error::Error>>;
 
-    fn parse_pnpm_lock(lockfile_path: &str) -> Result<Vec<String>, Box<dyn std::error::Error>>;
+    fn parse_pnpm_lock(lockfile_path: &str) -> Result<NodeLockfile, Box<dyn std::error::Error>>;
 
-    fn parse_podlock(lockfile_path: &str) -> Result<Vec<String>, Box<dyn std::error::Error>>;
+    fn parse_podlock(lockfile_path: &str) -> Result<Vec<PodLockEntry>, Box<dyn std::error::Error>>;
 
-    fn parse_settings_graddle(lockfile_path: &str) -> io::Result<()>;
+    fn parse_gradle_project(
+        build_file_path: &str,
+    ) -> Result<Vec<GradleDependency>, Box<dyn std::error::Error>>;
 }
 
 trait FileParser {
     fn get_node_module_package_info(
-        node_modules_path: Vec<String>,
-        root_directory: &PathBuf,
+        node_modules: Vec<NodeModule>,
+        root_directory: &Path,
+    ) -> Vec<ParsedPackageJson>;
+    fn get_pod_package_info(
+        pods: Vec<PodLockEntry>,
+        ios_directory: &Path,
+        root_directory: &Path,
+    ) -> Vec<ParsedPackageJson>;
+    fn get_gradle_package_info(
+        dependencies: Vec<GradleDependency>,
+        root_directory: &Path,
     ) -> Vec<ParsedPackageJson>;
     fn read_file(file_path: &str) -> io::Result<String>;
-    fn file_exists_in_directory(file_path: &str, root_directory: &PathBuf) -> bool;
+    fn file_exists_in_directory(file_path: &str, root_directory: &Path) -> bool;
     fn update_lock_file_path(
         lockfilepaths: &mut Vec<PathBuf>,
         lockfilepath: &str,
-        root_directory: &PathBuf,
+        root_directory: &Path,
     );
-    fn extract_yaml_library_name(key: &str) -> String;
-    fn parse_podlock_dependency_line(line: &str) -> Option<(String, String)>;
+    fn extract_yarn_library_name(descriptor: &str) -> Option<String>;
+    fn parse_podlock_pod_line(line: &str) -> Option<(String, String)>;
+}
+
+const LICENSE_FILE_NAMES: [&str; 14] = [
+    "LICENSE",
+    "license",
+    "license.md",
+    "LICENSE.md",
+    "license.txt",
+    "LICENSE.txt",
+    "LICENSE.rst",
+    "LICENCE",
+    "LICENCE.md",
+    "LICENCE.txt",
+    "COPYING",
+    "COPYING.txt",
+    "COPYRIGHT",
+    "COPYRIGHT.txt",
+];
+
+// Apache-2.0 packages must be distributed with their NOTICE file.
+const NOTICE_FILE_NAMES: [&str; 5] = ["NOTICE", "NOTICE.txt", "NOTICE.md", "notice", "notice.txt"];
+
+fn get_license_file_url(node_module_path: &String, root_directory: &Path) -> Option<String> {
+    let mut license_url: String = "".to_string();
+
+    LICENSE_FILE_NAMES.iter().any(|&file_name| {
+        let file_path = format!("{node_module_path}/{file_name}");
+        if file_exists_in_directory(&file_path, root_directory) {
+            license_url = format!("{node_module_path}/{file_name}");
+            return true;
+        }
+        false
+    });
+
+    Some(license_url)
+}
+
+/// Finds the first of `file_names` in a package directory, on disk or inside a Plug'n'Play
+/// zip archive, as a path relative to the project root.
+fn find_package_file(
+    directory: &str,
+    file_names: &[&str],
+    root_directory: &Path,
+) -> Option<String> {
+    let file_name = match pnp::split_zip_path(&format!("{directory}/")) {
+        Some((archive, inner_directory)) => {
+            let names = pnp::zip_file_names(&root_directory.join(archive), inner_directory);
+            file_names
+                .iter()
+                .find(|&&file_name| names.iter().any(|name| name == file_name))
+        }
+        None => file_names.iter().find(|&&file_name| {
+            file_exists_in_directory(&format!("{directory}/{file_name}"), root_directory)
+        }),
+    }?;
+    Some(format!("{directory}/{file_name}"))
+}
+
+/// Reads a text file of the project, which Plug'n'Play installs keep inside zip archives.
+fn read_project_file(file_path: &str, root_directory: &Path) -> Option<String> {
+    match pnp::split_zip_path(file_path) {
+        Some((archive, name)) => pnp::read_zip_entry(&root_directory.join(archive), name),
+        None => fs::read_to_string(root_directory.join(file_path)).ok(),
+    }
+}
+
+// Words of the copyright line of a license template, e.g. `Copyright (C) YEAR by AUTHOR` or
+// `Copyright (C) year  name of author`.
+const COPYRIGHT_PLACEHOLDER_WORDS: [&str; 15] = [
+    "copyright",
+    "c",
+    "year",
+    "yyyy",
+    "by",
+    "name",
+    "of",
+    "author",
+    "authors",
+    "email",
+    "fullname",
+    "owner",
+    "owners",
+    "holder",
+    "holders",
+];
+
+/// The copyright statements of license and NOTICE texts, once each.
+fn copyright_lines<'a>(texts: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut copyrights: Vec<String> = Vec::new();
+    let lines: Vec<&str> = texts.flat_map(str::lines).collect();
+    for (index, line) in lines.iter().enumerate() {
+        if !is_copyright_line(line) {
+            continue;
+        }
+        // The GNU licenses hold the copyright of their own text, not the package's.
+        if lines[index + 1..]
+            .iter()
+            .take(3)
+            .any(|next_line| next_line.contains("Everyone is permitted to copy"))
+        {
+            continue;
+        }
+        let line = line.split_whitespace().collect::<Vec<&str>>().join(" ");
+        // License templates, e.g. `Copyright [yyyy] [name of copyright owner]`.
+        let lowercase = line.to_lowercase();
+        if lowercase.contains("yyyy") || lowercase.contains("<year>") {
+            continue;
+        }
+        if lowercase
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .all(|word| COPYRIGHT_PLACEHOLDER_WORDS.contains(&word))
+        {
+            continue;
+        }
+        if !copyrights.contains(&line) {
+            copyrights.push(line);
+        }
+    }
+    copyrights
+}
+
+/// Reads the license and NOTICE files of a package for their text and copyright lines, and
+/// identifies the license from the file when the declared one names no known license.
+fn read_license_files(package: &mut ParsedPackageJson, root_directory: &Path) {
+    package.license_expression = LicenseExpression::parse(&package.license);
+
+    // `SEE LICENSE IN <file>` names the file, the one found next to the manifest otherwise.
+    let named_file = package
+        .license
+        .trim()
+        .strip_prefix("SEE LICENSE IN ")
+        .zip(package.paths.first())
+        .map(|(file, path)| format!("{path}/{}", file.trim()));
+    let license_files = named_file.into_iter().chain(
+        Some(package.license_url.clone())
+            .filter(|license_url| !license_url.is_empty() && !license_url.contains("://")),
+    );
+    if let Some((file_path, text)) = license_files
+        .filter_map(|file_path| {
+            Some((
+                file_path.clone(),
+                read_project_file(&file_path, root_directory)?,
+            ))
+        })
+        .next()
+    {
+        if package.license_expression.is_none() {
+            package.detected_license = detect_license(&text).map(|detected| DetectedLicense {
+                file: file_path,
+                ..detected
+            });
+        }
+        package.license_text = text;
+    }
+
+    // Jars keep their NOTICE in META-INF.
+    let notice_directories =
+        package
+            .paths
+            .first()
+            .into_iter()
+            .flat_map(|path| match package.ecosystem {
+                Ecosystem::Maven => vec![format!("{path}/META-INF"), path.clone()],
+                _ => vec![path.clone()],
+            });
+    if let Some(notice_url) = notice_directories
+        .into_iter()
+        .find_map(|directory| find_package_file(&directory, &NOTICE_FILE_NAMES, root_directory))
+    {
+        package.notice_text = read_project_file(&notice_url, root_directory).unwrap_or_default();
+        package.notice_url = notice_url;
+    }
+    package.copyrights =
+        copyright_lines([package.license_text.as_str(), package.notice_text.as_str()].into_iter());
+}
+
+pub(crate) fn file_exists_in_directory(file_path: &str, root_directory: &Path) -> bool {
+    let file_path = root_directory.join(file_path);
+
+    file_path.exists()
 }
 
-fn get_license_file_url(node_module_path: &String, root_directory: &PathBuf) -> Option<String> {
-    let mut license_url: String = "".to_string();
-    let potential_license_file_paths = [
-        "LICENSE",
-        "license",
-        "license.md",
-        "LICENSE.md",
-        "license.txt",
-        "LICENSE.txt",
-    ];
+// Express `path` relative to the project root so license urls look the same for every manager.
+fn relative_to_root(path: &Path, root_directory: &Path) -> String {
+    let path = format_file_path!(path);
+    path.strip_prefix(root_directory)
+        .unwrap_or(&path)
+        .to_str()
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn package_lock_key(path: &str, package: &PackageLockDevDependencies) -> String {
+    let name = package
+        .name
+        .clone()
+        .unwrap_or_else(|| match path.rsplit_once("node_modules/") {
+            Some((_, name)) => name.to_string(),
+            None => path.to_string(),
+        });
+    match &package.version {
+        Some(version) => format!("{name}@{version}"),
+        None => name,
+    }
+}
+
+/// Finds the package `name` required from `from` the way node does: in `from`'s own
+/// node_modules first, then in each ancestor's up to the root one. Workspace links resolve
+/// to the linked package.
+fn resolve_package_lock_dependency(
+    packages: &HashMap<String, PackageLockDevDependencies>,
+    from: &str,
+    name: &str,
+) -> Option<String> {
+    let mut base = from;
+    loop {
+        let candidate = if base.is_empty() {
+            format!("node_modules/{name}")
+        } else {
+            format!("{base}/node_modules/{name}")
+        };
+
+        if let Some(package) = packages.get(&candidate) {
+            return match (&package.link, &package.resolved) {
+                (true, Some(target)) if packages.contains_key(target) => Some(target.clone()),
+                _ => Some(candidate),
+            };
+        }
+        if base.is_empty() {
+            return None;
+        }
+        base = base
+            .rfind("/node_modules/")
+            .map_or("", |index| &base[..index]);
+    }
+}
+
+fn yarn_entry_key(entry: &NodeModule) -> String {
+    let name = entry.name.as_deref().unwrap_or_default();
+    match &entry.version {
+        Some(version) => format!("{name}@{version}"),
+        None => name.to_string(),
+    }
+}
+
+fn yaml_scalar(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(value) => Some(value.clone()),
+        serde_yaml::Value::Number(value) => Some(value.to_string()),
+        serde_yaml::Value::Bool(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+/// Splits a Yarn Berry locator or descriptor such as `@babel/core@npm:7.23.2` into the package
+/// name and its reference.
+fn split_yarn_berry_locator(locator: &str) -> Option<(&str, &str)> {
+    let name_end = locator
+        .char_indices()
+        .skip(1)
+        .find(|&(_, c)| c == '@')
+        .map(|(index, _)| index)?;
+    Some((&locator[..name_end], &locator[name_end + 1..]))
+}
+
+/// Splits a pnpm package key or dependency reference into the package name and its version,
+/// without the peer dependencies suffix.
+fn pnpm_package_id(key: &str, is_v5: bool) -> Option<(String, String)> {
+    let key = key.trim_start_matches('/');
+    let (name, version) = if is_v5 {
+        let (name, version) = key.rsplit_once('/')?;
+        (name, version.split('_').next().unwrap_or(version))
+    } else {
+        split_yarn_berry_locator(key.split('(').next().unwrap_or(key))?
+    };
+    Some((name.to_string(), version.to_string()))
+}
+
+/// Resolves a dependency listed by a pnpm package, e.g. `chalk: 2.4.2`,
+/// `'@babel/helper-module-transforms': 7.23.3(@babel/core@7.23.3)` or the
+/// `string-width-cjs: /string-width@4.2.3` alias, to the package key it points to.
+fn pnpm_dependency_id(
+    name: &str,
+    reference: &str,
+    is_v5: bool,
+    ids: &HashMap<String, String>,
+) -> Option<String> {
+    // Workspace packages.
+    if reference.starts_with("link:") {
+        return None;
+    }
+    let without_peers = reference.split('(').next().unwrap_or(reference);
+    if let Some(id) = ids.get(without_peers) {
+        return Some(id.clone());
+    }
+    let is_alias = reference.starts_with('/') || (!is_v5 && without_peers.contains('@'));
+    let id = match is_alias {
+        true => {
+            let (name, version) = pnpm_package_id(reference, is_v5)?;
+            format!("{name}@{version}")
+        }
+        false => {
+            let peers_separator = if is_v5 { '_' } else { '(' };
+            let version = reference.split(peers_separator).next().unwrap_or(reference);
+            format!("{name}@{version}")
+        }
+    };
+    Some(ids.get(&id).cloned().unwrap_or(id))
+}
+
+/// Returns the pnpm virtual store directory of a project, relative to it unless configured
+/// elsewhere, or `None` when packages are installed in a flat `node_modules`.
+///
+/// The layout pnpm actually installed with is recorded in `node_modules/.modules.yaml`, the
+/// configured one in `pnpm-workspace.yaml` or `.npmrc`.
+fn pnpm_virtual_store_directory(project_directory: &Path) -> Option<PathBuf> {
+    let mut node_linker = None;
+    let mut virtual_store_directory = None;
+
+    let modules_yaml =
+        std::fs::read_to_string(project_directory.join("node_modules/.modules.yaml"))
+            .ok()
+            .and_then(|content| serde_yaml::from_str::<serde_yaml::Value>(&content).ok());
+    if let Some(modules_yaml) = modules_yaml {
+        node_linker = modules_yaml["nodeLinker"].as_str().map(str::to_string);
+        // Older pnpm versions record an absolute path, newer ones a path relative to
+        // node_modules.
+        virtual_store_directory = modules_yaml["virtualStoreDir"]
+            .as_str()
+            .map(|directory| Path::new("node_modules").join(directory));
+    }
+
+    let workspace_yaml = std::fs::read_to_string(project_directory.join("pnpm-workspace.yaml"))
+        .ok()
+        .and_then(|content| serde_yaml::from_str::<serde_yaml::Value>(&content).ok());
+    if let Some(workspace_yaml) = workspace_yaml {
+        node_linker = node_linker.or(workspace_yaml["nodeLinker"].as_str().map(str::to_string));
+        virtual_store_directory = virtual_store_directory.or(workspace_yaml["virtualStoreDir"]
+            .as_str()
+            .map(PathBuf::from));
+    }
+
+    if let Ok(npmrc) = std::fs::read_to_string(project_directory.join(".npmrc")) {
+        for line in npmrc.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim().to_string();
+            match key.trim() {
+                "node-linker" => node_linker = node_linker.or(Some(value)),
+                "virtual-store-dir" => {
+                    virtual_store_directory = virtual_store_directory.or(Some(PathBuf::from(value)))
+                }
+                _ => (),
+            }
+        }
+    }
+
+    if node_linker.as_deref() == Some("hoisted") {
+        return None;
+    }
+    Some(virtual_store_directory.unwrap_or_else(|| PathBuf::from("node_modules/.pnpm")))
+}
+
+/// Finds a package in the virtual store, where it lives in
+/// `<name>@<version>[_<peers>]/node_modules/<name>` with the `/` of scoped names replaced by
+/// `+`.
+fn pnpm_virtual_store_path(
+    name: &str,
+    version: &str,
+    virtual_store_directory: &Path,
+    store_entries: &[String],
+    project_directory: &Path,
+) -> Option<String> {
+    let prefix = format!("{name}@{version}").replace(['/', ':'], "+");
+    store_entries
+        .iter()
+        .filter(|entry| {
+            entry.as_str() == prefix
+                || entry
+                    .strip_prefix(&prefix)
+                    .is_some_and(|peers| peers.starts_with('_') || peers.starts_with('('))
+        })
+        .map(|entry| {
+            virtual_store_directory
+                .join(entry)
+                .join("node_modules")
+                .join(name)
+        })
+        .find(|path| project_directory.join(path).join("package.json").exists())
+        .map(|path| {
+            path.strip_prefix(project_directory)
+                .map_or(path.clone(), Path::to_path_buf)
+        })
+        .and_then(|path| path.to_str().map(str::to_string))
+}
+
+/// Drops the `::locator=...` binding yarn appends to relative `portal:`, `link:` and `patch:`
+/// references, dependency lists leave it out.
+fn strip_yarn_berry_binding(descriptor: &str) -> &str {
+    descriptor.split("::").next().unwrap_or(descriptor)
+}
+
+/// Parses the YAML lockfile written by Yarn 2 and later, where `__metadata` holds the lockfile
+/// version and every key lists the `name@protocol:range` descriptors resolving to an entry.
+fn parse_yarn_berry_lock(
+    content: &str,
+    project_directory: &Path,
+) -> Result<NodeLockfile, Box<dyn std::error::Error>> {
+    let lockfile: HashMap<String, serde_yaml::Value> = serde_yaml::from_str(content)?;
+
+    // Each entry with the descriptors resolving to it.
+    let mut entries: Vec<(NodeModule, Vec<String>, Vec<String>)> = Vec::new();
+    // Each workspace with the descriptors it depends on.
+    let mut workspaces: Vec<(String, Vec<String>)> = Vec::new();
+    let mut workspace_paths: HashMap<String, String> = HashMap::new();
+
+    for (key, value) in lockfile {
+        if key == "__metadata" {
+            continue;
+        }
+        let entry: YarnBerryEntry = serde_yaml::from_value(value)?;
+        let Some(resolution) = entry.resolution else {
+            continue;
+        };
+        let Some((name, reference)) = split_yarn_berry_locator(&resolution) else {
+            continue;
+        };
+        let descriptors: Vec<String> = key
+            .split(',')
+            .map(|descriptor| strip_yarn_berry_binding(descriptor.trim()).to_string())
+            .collect();
+        let mut optional_descriptors = Vec::new();
+        let dependency_descriptors: Vec<String> = entry
+            .dependencies
+            .iter()
+            .filter_map(|(dependency, range)| {
+                let range = yaml_scalar(range)?;
+                // Ranges without a protocol use the default `npm:` one.
+                let descriptor = match range.contains(':') {
+                    true => format!("{dependency}@{range}"),
+                    false => format!("{dependency}@npm:{range}"),
+                };
+                let descriptor = strip_yarn_berry_binding(&descriptor).to_string();
+                let meta = entry.dependencies_meta.get(dependency);
+                if meta.is_some_and(|meta| meta["optional"].as_bool() == Some(true)) {
+                    optional_descriptors.push(descriptor.clone());
+                }
+                Some(descriptor)
+            })
+            .collect();
+
+        // `workspace:` entries are the project and its workspaces.
+        if let Some(path) = reference.strip_prefix("workspace:") {
+            let path = importer_path(path);
+            for descriptor in descriptors {
+                workspace_paths.insert(descriptor, path.clone());
+            }
+            workspaces.push((path, dependency_descriptors));
+            continue;
+        }
+        // `link:` entries are plain folders without metadata of their own.
+        if entry.language_name.as_deref() == Some("unknown") {
+            continue;
+        }
+        // Aliases such as `string-width-cjs@npm:string-width@^4.2.0` are installed under the
+        // alias.
+        let installed_name = descriptors
+            .first()
+            .and_then(|descriptor| split_yarn_berry_locator(descriptor))
+            .map_or(name, |(installed_name, _)| installed_name);
+
+        // `portal:` packages are used in place from their folder rather than installed, their
+        // version is the one of their package.json.
+        let is_soft_link = entry.link_type.as_deref() == Some("soft");
+        let path = match reference.split_once(':') {
+            Some((_, target)) if is_soft_link => strip_yarn_berry_binding(target).to_string(),
+            _ => format!("node_modules/{installed_name}"),
+        };
+
+        entries.push((
+            NodeModule {
+                path,
+                name: Some(name.to_string()),
+                version: entry
+                    .version
+                    .as_ref()
+                    .and_then(yaml_scalar)
+                    .filter(|_| !is_soft_link),
+                resolved: Some(resolution.clone()),
+                optional_dependencies: optional_descriptors,
+                ..NodeModule::default()
+            },
+            descriptors,
+            dependency_descriptors,
+        ));
+    }
+    // `patch:` entries share the name and version of the package they patch, keep the
+    // unpatched entry first so its resolution is reported.
+    entries.sort_by(|(a, _, _), (b, _, _)| {
+        let is_patch = |entry: &NodeModule| {
+            entry
+                .resolved
+                .as_deref()
+                .and_then(split_yarn_berry_locator)
+                .is_some_and(|(_, reference)| reference.starts_with("patch:"))
+        };
+        (yarn_entry_key(a), is_patch(a)).cmp(&(yarn_entry_key(b), is_patch(b)))
+    });
+
+    let importers = workspaces
+        .into_iter()
+        .map(|(path, dependency_descriptors)| {
+            let (workspace_dependencies, dependency_descriptors): (Vec<String>, Vec<String>) =
+                dependency_descriptors
+                    .into_iter()
+                    .partition(|descriptor| workspace_paths.contains_key(descriptor));
+            // The lockfile merges every dependency field of a workspace, its package.json tells
+            // them apart.
+            let package_json =
+                read_package_json(&project_directory.join(&path).join("package.json"))
+                    .unwrap_or_default();
+            let dependency_descriptors = dependency_descriptors
+                .into_iter()
+                .map(|descriptor| {
+                    let scope = split_yarn_berry_locator(&descriptor)
+                        .map(|(name, _)| package_json.dependency_scope(name))
+                        .unwrap_or_default();
+                    (descriptor, scope)
+                })
+                .collect();
+            (
+                Importer {
+                    path,
+                    workspace_dependencies: workspace_dependencies
+                        .iter()
+                        .map(|descriptor| workspace_paths[descriptor].clone())
+                        .collect(),
+                    ..Importer::default()
+                },
+                dependency_descriptors,
+            )
+        })
+        .collect();
+
+    Ok(link_yarn_entries(entries, importers))
+}
+
+/// Reads the direct dependencies of the root project and workspace members from their
+/// package.json, yarn v1 lockfiles do not record them.
+fn yarn_classic_importers(project_directory: &Path) -> Vec<(Importer, Vec<(String, Scope)>)> {
+    let members = workspace_members(project_directory);
+    let root = WorkspaceMember {
+        name: String::new(),
+        path: String::new(),
+    };
+
+    std::iter::once(&root)
+        .chain(members.iter())
+        .filter_map(|project| {
+            let package_json =
+                read_package_json(&project_directory.join(&project.path).join("package.json"))?;
+            let mut importer = Importer {
+                path: project.path.clone(),
+                ..Importer::default()
+            };
+            let mut descriptors = Vec::new();
+            for (name, range, scope) in package_json.dependency_ranges() {
+                match members.iter().find(|member| member.name == name) {
+                    Some(member) => importer.workspace_dependencies.push(member.path.clone()),
+                    None => descriptors.push((format!("{name}@{range}"), scope)),
+                }
+            }
+            Some((importer, descriptors))
+        })
+        .collect()
+}
+
+/// Normalizes the path of a workspace relative to the root, the root itself being `""`.
+fn importer_path(path: &str) -> String {
+    format_file_path!(Path::new(path))
+        .to_str()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Resolves the dependency descriptors of each yarn entry and project to `name@version` keys
+/// and keeps one entry per key.
+fn link_yarn_entries(
+    entries: Vec<(NodeModule, Vec<String>, Vec<String>)>,
+    importers: Vec<(Importer, Vec<(String, Scope)>)>,
+) -> NodeLockfile {
+    let keys: HashMap<String, String> = entries
+        .iter()
+        .flat_map(|(entry, descriptors, _)| {
+            let key = yarn_entry_key(entry);
+            descriptors
+                .iter()
+                .map(move |descriptor| (descriptor.clone(), key.clone()))
+        })
+        .collect();
+
+    let mut dependencies: Vec<NodeModule> = Vec::new();
+    let mut seen = HashSet::new();
+    for (mut entry, _, dependency_descriptors) in entries {
+        if seen.insert(yarn_entry_key(&entry)) {
+            entry.dependencies = dependency_descriptors
+                .iter()
+                .filter_map(|descriptor| keys.get(descriptor).cloned())
+                .collect();
+            entry.dependencies.sort();
+            entry.dependencies.dedup();
+            // Parsers leave descriptors there too.
+            entry.optional_dependencies = entry
+                .optional_dependencies
+                .iter()
+                .filter_map(|descriptor| keys.get(descriptor).cloned())
+                .collect();
+            dependencies.push(entry);
+        }
+    }
+
+    let importers: Vec<Importer> = importers
+        .into_iter()
+        .map(|(mut importer, dependency_descriptors)| {
+            importer.dependencies = dependency_descriptors
+                .iter()
+                .filter_map(|(descriptor, scope)| Some((keys.get(descriptor)?.clone(), *scope)))
+                .collect();
+            importer.dependencies.sort();
+            importer.dependencies.dedup();
+            importer
+        })
+        .collect();
+
+    // yarn lockfiles do not flag development dependencies.
+    assign_reachable_scopes(&mut dependencies, &importers);
+    NodeLockfile {
+        modules: dependencies,
+        importers,
+    }
+}
+
+/// Sets the scope of the modules the lockfile did not flag from the dependencies of the
+/// projects reaching them: production ones first, then optional and development ones. Those
+/// no project reaches are left unknown.
+fn assign_reachable_scopes(modules: &mut [NodeModule], importers: &[Importer]) {
+    if importers.is_empty() || modules.iter().all(|module| module.scope.is_some()) {
+        return;
+    }
+    let indexes: HashMap<String, usize> = modules
+        .iter()
+        .enumerate()
+        .map(|(index, module)| (yarn_entry_key(module), index))
+        .collect();
+
+    let order = [Scope::Production, Scope::Optional, Scope::Development];
+    let mut pending: [Vec<&String>; 3] = Default::default();
+    for (key, scope) in importers.iter().flat_map(|importer| &importer.dependencies) {
+        pending[order.iter().position(|s| s == scope).unwrap_or_default()].push(key);
+    }
+
+    let mut scopes: HashMap<usize, Scope> = HashMap::new();
+    for (rank, scope) in order.into_iter().enumerate() {
+        while let Some(key) = pending[rank].pop() {
+            let Some(&index) = indexes.get(key) else {
+                continue;
+            };
+            if scopes.contains_key(&index) {
+                continue;
+            }
+            scopes.insert(index, scope);
+            let module = &modules[index];
+            for dependency in &module.dependencies {
+                // What production packages only need optionally is reached with the optional
+                // dependencies of the projects.
+                match scope == Scope::Production
+                    && module.optional_dependencies.contains(dependency)
+                {
+                    true => pending[1].push(dependency),
+                    false => pending[rank].push(dependency),
+                }
+            }
+        }
+    }
+
+    for (index, scope) in scopes {
+        modules[index].scope.get_or_insert(scope);
+    }
+}
+
+fn read_package_json(package_json_path: &Path) -> Option<PackageJson> {
+    let content = DependencyFile::read_file(package_json_path.to_str()?).ok()?;
+    parse_package_json(&content)
+}
+
+fn parse_package_json(content: &str) -> Option<PackageJson> {
+    match serde_json::from_str::<PackageJson>(content) {
+        Ok(package_json) => Some(package_json),
+        Err(error) => {
+            eprintln!("Error parsing package.json: {error}");
+            None
+        }
+    }
+}
+
+/// Reads the package.json of a Plug'n'Play package straight from its zip archive in the yarn
+/// cache, along with the path of its license file inside the archive.
+fn read_pnp_package(
+    location: &str,
+    root_directory: &Path,
+) -> Option<(PackageJson, Option<String>)> {
+    let (archive, directory) = pnp::split_zip_path(location)?;
+    let archive_path = root_directory.join(archive);
+
+    let file_names = pnp::zip_file_names(&archive_path, directory);
+    let license_file_url = LICENSE_FILE_NAMES
+        .iter()
+        .find(|&&file_name| file_names.iter().any(|name| name == file_name))
+        .map(|file_name| format!("{location}{file_name}"));
+
+    let content = pnp::read_zip_entry(&archive_path, &format!("{directory}package.json"))?;
+    Some((parse_package_json(&content)?, license_file_url))
+}
+
+/// Looks for `node_modules/<dependent>/node_modules/<name>` installed at `version`.
+fn find_nested_copy(
+    name: &str,
+    version: &str,
+    root_directory: &Path,
+) -> Option<(String, PackageJson)> {
+    let mut dependents = Vec::new();
+    for entry in std::fs::read_dir(root_directory.join("node_modules"))
+        .ok()?
+        .flatten()
+    {
+        let dependent = entry.file_name().to_string_lossy().to_string();
+        if dependent.starts_with('@') {
+            if let Ok(scoped_entries) = std::fs::read_dir(entry.path()) {
+                for scoped_entry in scoped_entries.flatten() {
+                    dependents.push(format!(
+                        "{dependent}/{}",
+                        scoped_entry.file_name().to_string_lossy()
+                    ));
+                }
+            }
+        } else if !dependent.starts_with('.') {
+            dependents.push(dependent);
+        }
+    }
+
+    dependents.sort();
+    dependents.into_iter().find_map(|dependent| {
+        let path = format!("node_modules/{dependent}/node_modules/{name}");
+        let package_json_path = root_directory.join(&path).join("package.json");
+        if !package_json_path.exists() {
+            return None;
+        }
+        read_package_json(&package_json_path)
+            .filter(|package_json| package_json.version.as_deref() == Some(version))
+            .map(|package_json| (path, package_json))
+    })
+}
+
+fn format_podspec_authors(authors: PodspecAuthors) -> String {
+    match authors {
+        PodspecAuthors::Name(name) => name,
+        PodspecAuthors::Names(names) => names.join(", "),
+        PodspecAuthors::NamesWithEmail(names) => {
+            let mut authors: Vec<String> = names
+                .into_iter()
+                .map(|(name, email)| match email {
+                    Some(email) if !email.is_empty() => format!("{name} <{email}>"),
+                    _ => name,
+                })
+                .collect();
+            authors.sort();
+            authors.join(", ")
+        }
+    }
+}
+
+impl FileParser for DependencyFile {
+    /// Builds the report entry of every node module.
+    ///
+    /// What the lockfile recorded (name, version, license) is used as is, the module's
+    /// `package.json` is only read for the fields the lockfile lacks. A module that is not
+    /// installed is still reported when the lockfile knows its version or license.
+    ///
+    /// # Parameters
+    ///
+    /// * `node_modules` - The modules returned by the lockfile parsers.
+    /// * `root_directory` - The project root, module paths are relative to it.
+    fn get_node_module_package_info(
+        node_modules: Vec<NodeModule>,
+        root_directory: &Path,
+    ) -> Vec<ParsedPackageJson> {
+        let mut node_module_info: Vec<ParsedPackageJson> = Vec::new();
+        // Modules neither installed nor described by the lockfile, left out of the report.
+        let mut unreadable: Vec<String> = Vec::new();
+        // Plug'n'Play installs have no node_modules, packages stay zipped in the yarn cache.
+        let pnp_manifest = PnpManifest::from_project(root_directory);
+
+        for node_module in node_modules {
+            let mut node_module_path = node_module.path;
+            let package_json_path = root_directory.join(&node_module_path).join("package.json");
+            let is_locked = node_module.license.is_some() || node_module.version.is_some();
+            let name = node_module.name.unwrap_or_else(|| {
+                match node_module_path.rsplit_once("node_modules/") {
+                    Some((_, name)) => name.to_string(),
+                    None => node_module_path.clone(),
+                }
+            });
+
+            let pnp_location = pnp_manifest
+                .as_ref()
+                .zip(node_module.resolved.as_deref())
+                .and_then(|(pnp_manifest, locator)| pnp_manifest.location(locator))
+                .map(|location| location.trim_start_matches("./"));
+            let mut pnp_license_file_url = None;
+
+            let mut package_json = if package_json_path.exists() {
+                read_package_json(&package_json_path)
+            } else if let Some((location, (pnp_package_json, license_file_url))) = pnp_location
+                .and_then(|location| Some((location, read_pnp_package(location, root_directory)?)))
+            {
+                node_module_path = location.trim_end_matches('/').to_string();
+                pnp_license_file_url = Some(license_file_url.unwrap_or_default());
+                Some(pnp_package_json)
+            } else {
+                None
+            };
+
+            // yarn.lock does not record where a package is installed, a version other than the
+            // hoisted one lives in the node_modules of the package depending on it.
+            if let (Some(locked_version), Some(installed)) = (&node_module.version, &package_json) {
+                if installed.version.as_ref() != Some(locked_version) {
+                    package_json = None;
+                    if let Some((nested_path, nested_package_json)) =
+                        find_nested_copy(&name, locked_version, root_directory)
+                    {
+                        node_module_path = nested_path;
+                        package_json = Some(nested_package_json);
+                    }
+                }
+            }
+
+            if package_json.is_none() && !is_locked {
+                unreadable.push(node_module_path);
+                continue;
+            }
+            let package_json = package_json.unwrap_or_default();
+            let license_file_url = pnp_license_file_url
+                .or_else(|| get_license_file_url(&node_module_path, root_directory));
+            let repository_url = package_json.repository_url();
+            let author = package_json.author_name();
+            let contributors = package_json.contributors();
+            let maintainers = package_json.maintainers();
+            let license = node_module.license.or(package_json.license_id());
+
+            node_module_info.push(ParsedPackageJson {
+                name: if name.is_empty() {
+                    package_json.name.unwrap_or_default()
+                } else {
+                    name
+                },
+                version: node_module
+                    .version
+                    .or(package_json.version)
+                    .unwrap_or("".to_string()),
+                description: package_json.description.unwrap_or("".to_string()),
+                repository_url: repository_url.unwrap_or("".to_string()),
+                homepage: package_json.homepage.unwrap_or("".to_string()),
+                author: author.unwrap_or("".to_string()),
+                contributors,
+                maintainers,
+                license: license.unwrap_or("".to_string()),
+                license_expression: None,
+                detected_license: None,
+                license_url: license_file_url.unwrap_or("".to_string()),
+                declared_license_url: String::new(),
+                license_text: String::new(),
+                notice_url: String::new(),
+                notice_text: String::new(),
+                copyrights: Vec::new(),
+                resolved: node_module.resolved.unwrap_or_default(),
+                integrity: node_module.integrity.unwrap_or_default(),
+                paths: vec![node_module_path],
+                ecosystem: Ecosystem::Npm,
+                dependencies: node_module.dependencies,
+                scope: node_module.scope,
+                direct: false,
+            })
+        }
+        if !unreadable.is_empty() {
+            eprintln!(
+                "Skipping {} modules with no package.json in {:?} and no version in the lockfile: {}",
+                unreadable.len(),
+                root_directory,
+                unreadable.join(", ")
+            );
+        }
+        node_module_info
+    }
+
+    /// Resolves every pod listed in a Podfile.lock to its license metadata.
+    ///
+    /// Metadata is read, in order of preference, from the podspec CocoaPods stored in
+    /// `Pods/Local Podspecs`, a `.podspec.json` referenced by `:podspec:` or found under `:path:`,
+    /// and finally the `package.json` of the npm package that vendors a `:path:` pod. Ruby
+    /// podspecs are only read from the JSON CocoaPods caches in `Pods/Local Podspecs` on
+    /// `pod install`, the pods resolved from none of these are reported as unresolved.
+    /// License files are looked up in the podspec `license.file`, `Pods/<Name>/` and the
+    /// `:path:` directory.
+    ///
+    /// # Parameters
+    ///
+    /// * `pods` - The pods returned by `parse_podlock`.
+    /// * `ios_directory` - The directory containing the Podfile.lock.
+    /// * `root_directory` - The project root, license urls are relative to it.
+    fn get_pod_package_info(
+        pods: Vec<PodLockEntry>,
+        ios_directory: &Path,
+        root_directory: &Path,
+    ) -> Vec<ParsedPackageJson> {
+        let mut pod_info: Vec<ParsedPackageJson> = Vec::new();
+        let mut unresolved_pods: Vec<String> = Vec::new();
+        let pods_directory = ios_directory.join("Pods");
+
+        for pod in pods {
+            let pod_source_directory = pod
+                .path
+                .as_ref()
+                .map(|path| format_file_path!(ios_directory.join(path)));
+
+            // A Ruby `:podspec:` can't be evaluated, CocoaPods stores it as JSON in the cache.
+            let mut podspec_paths = vec![pods_directory
+                .join("Local Podspecs")
+                .join(format!("{}.podspec.json", pod.name))];
+            if let Some(podspec) = pod.podspec.as_ref().filter(|p| p.ends_with(".json")) {
+                podspec_paths.push(ios_directory.join(podspec));
+            }
+            if let Some(directory) = &pod_source_directory {
+                podspec_paths.push(directory.join(format!("{}.podspec.json", pod.name)));
+            }
+
+            let podspec = podspec_paths.iter().find_map(|podspec_path| {
+                let content = DependencyFile::read_file(podspec_path.to_str()?).ok()?;
+                match serde_json::from_str::<PodspecJson>(&content) {
+                    Ok(podspec) => Some(podspec),
+                    Err(error) => {
+                        eprintln!("Error parsing {:?}: {error}", podspec_path);
+                        None
+                    }
+                }
+            });
 
-    potential_license_file_paths.iter().any(|&file_name| {
-        let file_path = format!("{node_module_path}/{file_name}");
-        if file_exists_in_directory(&file_path, root_directory) {
-            license_url = format!("{node_module_path}/{file_name}");
-            return true;
-        }
-        false
-    });
+            // Pods vendored by npm packages usually build their podspec from the package.json.
+            let package_json = pod_source_directory.as_ref().and_then(|directory| {
+                [directory.clone(), format_file_path!(directory.join(".."))]
+                    .iter()
+                    .find_map(|candidate| {
+                        let content =
+                            DependencyFile::read_file(candidate.join("package.json").to_str()?)
+                                .ok()?;
+                        serde_json::from_str::<PackageJson>(&content).ok()
+                    })
+            });
 
-    Some(license_url)
-}
+            if podspec.is_none() && package_json.is_none() {
+                unresolved_pods.push(pod.name.clone());
+            }
 
-pub(crate) fn file_exists_in_directory(file_path: &str, root_directory: &PathBuf) -> bool {
-    let file_path = root_directory.join(file_path);
+            let mut license = String::new();
+            let mut license_file = None;
+            let install_directory = pod_source_directory
+                .clone()
+                .unwrap_or_else(|| pods_directory.join(&pod.name));
+            let mut parsed = ParsedPackageJson {
+                name: pod.name.clone(),
+                version: pod.version.clone(),
+                ecosystem: Ecosystem::CocoaPods,
+                paths: vec![relative_to_root(&install_directory, root_directory)],
+                dependencies: pod.dependencies.clone(),
+                ..ParsedPackageJson::default()
+            };
 
-    file_path.exists()
-}
+            if let Some(podspec) = podspec {
+                match podspec.license {
+                    Some(PodspecLicense::Type(license_type)) => license = license_type,
+                    Some(PodspecLicense::Detailed { license_type, file }) => {
+                        license = license_type.unwrap_or_default();
+                        license_file = file;
+                    }
+                    None => (),
+                }
+                if parsed.version.is_empty() {
+                    parsed.version = podspec.version.unwrap_or_default();
+                }
+                if parsed.name.is_empty() {
+                    parsed.name = podspec.name.unwrap_or_default();
+                }
+                parsed.description = podspec.summary.or(podspec.description).unwrap_or_default();
+                parsed.homepage = podspec.homepage.unwrap_or_default();
+                parsed.author = podspec
+                    .authors
+                    .map(format_podspec_authors)
+                    .unwrap_or_default();
+                parsed.repository_url = podspec
+                    .source
+                    .and_then(|source| {
+                        source
+                            .get("git")
+                            .or(source.get("http"))
+                            .and_then(Value::as_str)
+                            .map(str::to_string)
+                    })
+                    .unwrap_or_default();
+            }
 
-impl FileParser for DependencyFile {
-    fn get_node_module_package_info(
-        node_modules_path: Vec<String>,
-        root_directory: &PathBuf,
-    ) -> Vec<ParsedPackageJson> {
-        let mut node_module_info: Vec<ParsedPackageJson> = Vec::new();
+            if let Some(package_json) = package_json {
+                if license.is_empty() {
+                    license = package_json.license_id().unwrap_or_default();
+                }
+                if parsed.author.is_empty() {
+                    parsed.author = package_json.author_name().unwrap_or_default();
+                }
+                if parsed.repository_url.is_empty() {
+                    parsed.repository_url = package_json.repository_url().unwrap_or_default();
+                }
+                parsed.contributors = package_json.contributors();
+                parsed.maintainers = package_json.maintainers();
+                if parsed.description.is_empty() {
+                    parsed.description = package_json.description.unwrap_or_default();
+                }
+                if parsed.homepage.is_empty() {
+                    parsed.homepage = package_json.homepage.unwrap_or_default();
+                }
+            }
+            parsed.license = license;
+
+            let mut license_directories = vec![pods_directory.join(&pod.name)];
+            if let Some(directory) = &pod_source_directory {
+                license_directories.push(directory.clone());
+                license_directories.push(format_file_path!(directory.join("..")));
+            }
+
+            parsed.license_url = license_directories
+                .iter()
+                .find_map(|directory| {
+                    let directory = relative_to_root(directory, root_directory);
+                    if let Some(file) = &license_file {
+                        let file_path = format!("{directory}/{file}");
+                        if file_exists_in_directory(&file_path, root_directory) {
+                            return Some(file_path);
+                        }
+                    }
+                    get_license_file_url(&directory, root_directory)
+                        .filter(|license_url| !license_url.is_empty())
+                })
+                .unwrap_or_default();
+
+            pod_info.push(parsed);
+        }
+        if !unresolved_pods.is_empty() {
+            eprintln!(
+                "No podspec found for {} pods in {:?}, their license is unknown until `pod install` caches them in Pods/Local Podspecs: {}",
+                unresolved_pods.len(),
+                ios_directory,
+                unresolved_pods.join(", ")
+            );
+        }
+        pod_info
+    }
 
-        for node_module_path in node_modules_path {
-            let module_path = root_directory.join(&node_module_path);
-            let package_json_path = module_path.join("package.json");
+    /// Resolves Maven artifacts to their license metadata from the POMs found in the local
+    /// Gradle cache and `~/.m2`, following `<parent>` chains for inherited fields, and adds
+    /// the artifacts their POMs depend on. License and NOTICE texts are read from the
+    /// `META-INF` of the cached jar or aar.
+    ///
+    /// # Parameters
+    ///
+    /// * `dependencies` - The artifacts returned by `parse_gradle_project`.
+    /// * `root_directory` - The project root, license urls are relative to it.
+    ///
+    /// # Returns
+    ///
+    /// The given artifacts in order, followed by the ones only depended on through POMs.
+    fn get_gradle_package_info(
+        dependencies: Vec<GradleDependency>,
+        root_directory: &Path,
+    ) -> Vec<ParsedPackageJson> {
+        let repositories = MavenRepositories::from_environment();
+        // Versions managed by a BOM or plugin are only known once resolved, use the cached
+        // one when it is unambiguous.
+        let resolve_version = |group: &str, artifact: &str, version: &str| match repositories
+            .cached_versions(group, artifact)
+            .as_slice()
+        {
+            [cached_version] if version.is_empty() => cached_version.clone(),
+            _ => version.to_string(),
+        };
 
-            if let Ok(node_package_json) =
-                DependencyFile::read_file(package_json_path.to_str().unwrap_or_default())
+        // Gradle resolves one version of each artifact, the first one reached stands for it.
+        let mut versions: HashMap<String, String> = HashMap::new();
+        let mut queue: VecDeque<(String, String, String)> = VecDeque::new();
+        for dependency in dependencies {
+            let version =
+                resolve_version(&dependency.group, &dependency.artifact, &dependency.version);
+            if versions
+                .insert(dependency.coordinate(), version.clone())
+                .is_none()
             {
-                match serde_json::from_str::<PackageJson>(&node_package_json) {
-                    Ok(package_json) => {
-                        let license_file_url =
-                            get_license_file_url(&node_module_path, root_directory);
-
-                        node_module_info.push(ParsedPackageJson {
-                            name: package_json.name.unwrap_or("".to_string()),
-                            description: package_json.description.unwrap_or("".to_string()),
-                            repository_url: package_json
-                                .repository
-                                .unwrap_or(Repository {
-                                    url: Some("".to_string()),
-                                })
-                                .url
-                                .unwrap_or("".to_string()),
-                            author: package_json.author.unwrap_or("".to_string()),
-                            license: package_json.license.unwrap_or("".to_string()),
-                            license_url: license_file_url.unwrap_or("".to_string()),
-                        })
+                queue.push_back((dependency.group, dependency.artifact, version));
+            }
+        }
+
+        let mut packages = Vec::new();
+        let mut unlicensed = Vec::new();
+        while let Some((group, artifact, version)) = queue.pop_front() {
+            let coordinate = format!("{group}:{artifact}");
+            let pom = repositories
+                .resolve(&group, &artifact, &version)
+                .unwrap_or_default();
+            if pom.licenses.is_empty() {
+                unlicensed.push(format!("{coordinate}:{version}"));
+            }
+
+            let mut package_dependencies = Vec::new();
+            for dependency in pom.dependencies {
+                let dependency_coordinate = format!("{}:{}", dependency.group, dependency.artifact);
+                let dependency_version = match versions.get(&dependency_coordinate) {
+                    Some(version) => version.clone(),
+                    None => {
+                        let version = resolve_version(
+                            &dependency.group,
+                            &dependency.artifact,
+                            &dependency.version,
+                        );
+                        versions.insert(dependency_coordinate.clone(), version.clone());
+                        queue.push_back((dependency.group, dependency.artifact, version.clone()));
+                        version
                     }
-                    Err(error) => eprintln!("Error parsing package.json: {error}"),
-                }
-            } else {
-                eprintln!("Failed to read file at {:?}", package_json_path);
+                };
+                package_dependencies.push(match dependency_version.is_empty() {
+                    true => dependency_coordinate,
+                    false => format!("{dependency_coordinate}@{dependency_version}"),
+                });
             }
+
+            let archive = repositories
+                .find_archive(&group, &artifact, &version)
+                .map(|archive| relative_to_root(&archive, root_directory));
+            let license_url = archive
+                .iter()
+                .flat_map(|archive| [format!("{archive}/META-INF"), archive.clone()])
+                .find_map(|directory| {
+                    find_package_file(&directory, &LICENSE_FILE_NAMES, root_directory)
+                })
+                .unwrap_or_default();
+
+            packages.push(ParsedPackageJson {
+                name: coordinate,
+                version,
+                ecosystem: Ecosystem::Maven,
+                paths: archive.into_iter().collect(),
+                description: pom.description.or(pom.name).unwrap_or_default(),
+                repository_url: pom.scm_url.unwrap_or_default(),
+                homepage: pom.url.unwrap_or_default(),
+                author: pom.developers.join(", "),
+                license: pom
+                    .licenses
+                    .iter()
+                    .map(|license| license.name.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(" OR "),
+                license_url,
+                declared_license_url: pom
+                    .licenses
+                    .iter()
+                    .find_map(|license| license.url.clone())
+                    .unwrap_or_default(),
+                dependencies: package_dependencies,
+                ..ParsedPackageJson::default()
+            });
         }
-        node_module_info
+        if !unlicensed.is_empty() {
+            eprintln!(
+                "No license found in the local Maven repositories for {} artifacts: {}",
+                unlicensed.len(),
+                unlicensed.join(", ")
+            );
+        }
+        packages
     }
 
     fn read_file(file_path: &str) -> io::Result<String> {
@@ -173,267 +1778,851 @@ impl FileParser for DependencyFile {
         Ok(contents)
     }
 
-    fn file_exists_in_directory(file_path: &str, root_directory: &PathBuf) -> bool {
-        let file_path = root_directory.clone().join(file_path);
+    fn file_exists_in_directory(file_path: &str, root_directory: &Path) -> bool {
+        let file_path = root_directory.join(file_path);
         file_path.exists()
     }
 
     fn update_lock_file_path(
         lockfilepaths: &mut Vec<PathBuf>,
         lockfilepath: &str,
-        root_directory: &PathBuf,
+        root_directory: &Path,
     ) {
-        if Self::file_exists_in_directory(lockfilepath, &root_directory) {
-            lockfilepaths.push(root_directory.join(lockfilepath));
+        if Self::file_exists_in_directory(lockfilepath, root_directory) {
+            lockfilepaths.push(format_file_path!(root_directory.join(lockfilepath)));
         }
     }
 
-    fn extract_yaml_library_name(key: &str) -> String {
-        // Split the key by '/'
-        let parts: Vec<&str> = key.split('/').collect();
+    fn extract_yarn_library_name(descriptor: &str) -> Option<String> {
+        let trimmed_identifier = descriptor.trim().trim_matches('"');
 
-        // The package name is usually the last part for non-scoped packages,
-        // or the last two parts for scoped packages.
-        match parts.as_slice() {
-            // Scoped package (e.g., "/@babel/code-frame/7.10.4")
-            [_, scope, rest, ..] if scope.starts_with('@') => {
-                let name = rest.split('@').next().unwrap_or("");
-                format!("{}/{}", scope, name)
-            }
-            // Non-scoped package (e.g., "/lodash/4.17.15")
-            [_, rest, ..] => {
-                let name = rest.split('@').next().unwrap_or("");
-                name.to_string()
-            }
-            // Other cases (e.g., malformed or unexpected format)
-            _ => String::new(),
-        }
-    }
+        let library_name = if trimmed_identifier.starts_with('@') {
+            // Scoped package
+            let slash_index = trimmed_identifier.find('/')?;
+            &trimmed_identifier[..trimmed_identifier[slash_index..]
+                .find('@')
+                .map_or(trimmed_identifier.len(), |idx_to_trim| {
+                    slash_index + idx_to_trim
+                })]
+        } else {
+            // Regular package
+            &trimmed_identifier[..trimmed_identifier
+                .find('@')
+                .unwrap_or(trimmed_identifier.len())]
+        };
 
-    fn parse_podlock_dependency_line(line: &str) -> Option<(String, String)> {
-        let line = line.trim();
-        if line.is_empty() || line.chars().all(char::is_uppercase) {
+        if library_name.is_empty() {
             return None;
         }
+        Some(library_name.to_string())
+    }
 
-        if let Some((lib_name, _)) = line.split_once('(') {
-            let lib_name = lib_name.trim().trim_matches('-').to_string();
-
-            let path = if line.contains("~>") {
-                "./Pods".to_string()
-            } else {
-                line.split(" (from `")
-                    .nth(1)
-                    .unwrap_or("")
-                    .trim_end_matches(')')
-                    .trim()
-                    .to_string()
-            };
+    fn parse_podlock_pod_line(line: &str) -> Option<(String, String)> {
+        // Pod entries look like `RCT-Folly/Default (2021.07.22.00)`, subspecs share the
+        // version and license of their root pod.
+        let line = line.trim().trim_matches('"');
+        let (lib_name, version) = line.split_once(" (")?;
+        let lib_name = lib_name.split('/').next().unwrap_or(lib_name).trim();
+        let version = version.trim_end_matches(')').trim();
 
-            return Some((lib_name, path));
+        if lib_name.is_empty() {
+            return None;
         }
 
-        None
+        Some((lib_name.to_string(), version.to_string()))
     }
 }
 
 impl Parser for DependencyFile {
-    fn parse_package_lock(lockfile_path: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-        /// Parses a package-lock.json file and returns a list of dependencies.
-        ///
-        /// # Parameters
-        ///
-        /// * `lockfile_path` - The path to the package-lock.json file.
-        ///
-        /// # Returns
-        ///
-        /// A `Vec` of dependencies, or an error if the file could not be parsed.
-        let file_content = DependencyFile::read_file(lockfile_path).unwrap();
+    /// Parses a package-lock.json file and returns a list of dependencies.
+    ///
+    /// # Parameters
+    ///
+    /// * `lockfile_path` - The path to the package-lock.json file.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec` of dependencies, or an error if the file could not be parsed.
+    fn parse_package_lock(lockfile_path: &str) -> Result<NodeLockfile, Box<dyn std::error::Error>> {
+        let file_content = DependencyFile::read_file(lockfile_path)?;
         let package_lock_json = serde_json::from_str::<PackageLockJson>(&file_content);
+        let project_directory = Path::new(lockfile_path).parent().unwrap_or(Path::new(""));
 
         match package_lock_json {
             Ok(package_lock_json) => {
                 let mut dependencies = Vec::new();
+                let mut importers = Vec::new();
                 if let Some(packages) = package_lock_json.packages {
-                    for (package_name, _) in packages {
-                        dependencies.push(package_name);
+                    let keys: HashMap<&String, String> = packages
+                        .iter()
+                        .map(|(path, package)| (path, package_lock_key(path, package)))
+                        .collect();
+                    // The root project is keyed `""` and workspace members by their path.
+                    let mut project_paths: HashSet<String> = workspace_members(project_directory)
+                        .into_iter()
+                        .map(|member| member.path)
+                        .collect();
+                    project_paths.insert(String::new());
+
+                    for (package_name, package) in &packages {
+                        if project_paths.contains(package_name) {
+                            let mut importer = Importer {
+                                path: package_name.clone(),
+                                ..Importer::default()
+                            };
+                            for (dependencies, scope) in [
+                                (&package.dependencies, Scope::Production),
+                                (&package.peer_dependencies, Scope::Production),
+                                (&package.optional_dependencies, Scope::Optional),
+                                (&package.dev_dependencies, Scope::Development),
+                            ] {
+                                for dependency in dependencies.iter().flat_map(HashMap::keys) {
+                                    match resolve_package_lock_dependency(
+                                        &packages,
+                                        package_name,
+                                        dependency,
+                                    ) {
+                                        Some(path) if project_paths.contains(&path) => {
+                                            importer.workspace_dependencies.push(path)
+                                        }
+                                        Some(path) => importer.dependencies.extend(
+                                            keys.get(&path).map(|key| (key.clone(), scope)),
+                                        ),
+                                        None => (),
+                                    }
+                                }
+                            }
+                            importers.push(importer);
+                            continue;
+                        }
+                        // Links to workspace members.
+                        if package.link
+                            && package
+                                .resolved
+                                .as_ref()
+                                .is_some_and(|target| project_paths.contains(target))
+                        {
+                            continue;
+                        }
+
+                        // lockfileVersion 2/3 record the license declared by each package.
+                        let license = package.license.as_ref().and_then(|license| match license {
+                            Value::String(license) => Some(license.clone()),
+                            Value::Object(license) => license
+                                .get("type")
+                                .and_then(Value::as_str)
+                                .map(str::to_string),
+                            _ => None,
+                        });
+
+                        let mut package_dependencies: Vec<String> = [
+                            &package.dependencies,
+                            &package.optional_dependencies,
+                            &package.peer_dependencies,
+                        ]
+                        .into_iter()
+                        .flatten()
+                        .flat_map(HashMap::keys)
+                        .filter_map(|dependency| {
+                            resolve_package_lock_dependency(&packages, package_name, dependency)
+                        })
+                        .filter(|path| !project_paths.contains(path))
+                        .filter_map(|path| keys.get(&path).cloned())
+                        .collect();
+                        package_dependencies.sort();
+                        package_dependencies.dedup();
+
+                        // `devOptional` packages are optional dependencies of development ones.
+                        let scope = if package.dev || package.dev_optional {
+                            Scope::Development
+                        } else if package.optional {
+                            Scope::Optional
+                        } else {
+                            Scope::Production
+                        };
+
+                        dependencies.push(NodeModule {
+                            path: package_name.clone(),
+                            name: package.name.clone(),
+                            version: package.version.clone(),
+                            license,
+                            resolved: package.resolved.clone(),
+                            integrity: package.integrity.clone(),
+                            dependencies: package_dependencies,
+                            scope: Some(scope),
+                            ..NodeModule::default()
+                        });
                     }
                 }
-                Ok(dependencies)
+                Ok(NodeLockfile {
+                    modules: dependencies,
+                    importers,
+                })
             }
             Err(error) => Err(Box::new(error)),
         }
     }
 
-    fn parse_yarn_lock(lockfile_path: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-        /// Parses a yarn.lock file and returns a list of dependencies.
-        ///
-        /// # Parameters
-        ///
-        /// * `lockfile_path` - The path to the yarn.lock file.
-        ///
-        /// # Returns
-        ///
-        /// A `Vec` of dependencies, or an error if the file could not be parsed.
+    /// Parses a yarn.lock file and returns a list of dependencies.
+    ///
+    /// # Parameters
+    ///
+    /// * `lockfile_path` - The path to the yarn.lock file.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec` of dependencies, or an error if the file could not be parsed.
+    fn parse_yarn_lock(lockfile_path: &str) -> Result<NodeLockfile, Box<dyn std::error::Error>> {
         let yarn_lock = <DependencyFile as FileParser>::read_file(lockfile_path);
 
         match yarn_lock {
             Ok(yarn_lock) => {
-                let mut dependencies = Vec::new();
-                let mut seen = HashSet::new();
+                if yarn_lock
+                    .lines()
+                    .any(|line| line.starts_with("__metadata:"))
+                {
+                    let project_directory =
+                        Path::new(lockfile_path).parent().unwrap_or(Path::new(""));
+                    return parse_yarn_berry_lock(&yarn_lock, project_directory);
+                }
+
+                // Each entry with the descriptors resolving to it and the descriptors it
+                // depends on.
+                let mut entries: Vec<(NodeModule, Vec<String>, Vec<String>)> = Vec::new();
+                let mut in_dependencies = false;
+                let mut in_optional_dependencies = false;
 
                 for line in yarn_lock.lines() {
-                    if let Some(colon_index) = line.find(':') {
-                        let package_identifiers = line[..colon_index].split(',');
-
-                        for package_identifier in package_identifiers {
-                            let trimmed_identifier = package_identifier.trim();
-
-                            let library_name = if trimmed_identifier.starts_with('@') {
-                                // Scoped package
-                                if let Some(slash_index) = trimmed_identifier.find('/') {
-                                    &trimmed_identifier[..trimmed_identifier[slash_index..]
-                                        .find('@')
-                                        .map_or(trimmed_identifier.len(), |idx_to_trim| {
-                                            slash_index + idx_to_trim
-                                        })]
-                                } else {
-                                    continue; // Invalid format, skip
-                                }
-                            } else {
-                                // Regular package
-                                &trimmed_identifier[..trimmed_identifier
-                                    .find('@')
-                                    .unwrap_or(trimmed_identifier.len())]
-                            };
+                    if line.trim().is_empty() || line.starts_with('#') {
+                        continue;
+                    }
 
-                            if library_name.to_string() != ""
-                                && seen.insert(library_name.to_string())
-                            {
-                                dependencies.push(format!("node_modules/{library_name}"));
+                    // Entries start at column 0 with every descriptor resolving to them,
+                    // e.g. `"@babel/code-frame@^7.0.0", "@babel/code-frame@^7.12.13":`.
+                    if !line.starts_with(' ') {
+                        let descriptors: Vec<String> = line
+                            .trim_end_matches(':')
+                            .split(',')
+                            .map(|descriptor| descriptor.trim().trim_matches('"').to_string())
+                            .collect();
+                        if let Some(library_name) = descriptors.iter().find_map(|d| {
+                            <DependencyFile as FileParser>::extract_yarn_library_name(d)
+                        }) {
+                            entries.push((
+                                NodeModule {
+                                    path: format!("node_modules/{library_name}"),
+                                    name: Some(library_name),
+                                    ..NodeModule::default()
+                                },
+                                descriptors,
+                                Vec::new(),
+                            ));
+                        }
+                        in_dependencies = false;
+                        in_optional_dependencies = false;
+                        continue;
+                    }
+
+                    let Some((entry, _, dependency_descriptors)) = entries.last_mut() else {
+                        continue;
+                    };
+
+                    // `dependencies:` and `optionalDependencies:` list `name "range"` pairs.
+                    if line.starts_with("    ") {
+                        if in_dependencies {
+                            let dependency = line.trim();
+                            if let Some((name, range)) = dependency.split_once(' ') {
+                                let descriptor = format!(
+                                    "{}@{}",
+                                    name.trim_matches('"'),
+                                    range.trim().trim_matches('"')
+                                );
+                                if in_optional_dependencies {
+                                    entry.optional_dependencies.push(descriptor.clone());
+                                }
+                                dependency_descriptors.push(descriptor);
                             }
                         }
+                        continue;
+                    }
+
+                    let field = line.trim();
+                    in_optional_dependencies = field == "optionalDependencies:";
+                    in_dependencies = field == "dependencies:" || in_optional_dependencies;
+                    let Some((field, value)) = field.split_once(' ') else {
+                        continue;
+                    };
+                    let value = value.trim().trim_matches('"').to_string();
+                    match field {
+                        "version" => entry.version = Some(value),
+                        "resolved" => entry.resolved = Some(value),
+                        "integrity" => entry.integrity = Some(value),
+                        _ => (),
                     }
                 }
-                Ok(dependencies)
+
+                let project_directory = Path::new(lockfile_path).parent().unwrap_or(Path::new(""));
+                Ok(link_yarn_entries(
+                    entries,
+                    yarn_classic_importers(project_directory),
+                ))
             }
             Err(error) => Err(Box::new(error)),
         }
     }
 
-    fn parse_pnpm_lock(lockfile_path: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-        /// Parses a pnpm-lock.yaml file and returns a list of dependencies.
-        ///
-        /// # Parameters
-        ///
-        /// * `lockfile_path` - The path to the pnpm-lock.yaml file.
-        ///
-        /// # Returns
-        ///
-        /// A `Vec` of dependencies, or an error if the file could not be parsed.
+    /// Parses a pnpm-lock.yaml file and returns a list of dependencies.
+    ///
+    /// # Parameters
+    ///
+    /// * `lockfile_path` - The path to the pnpm-lock.yaml file.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec` of dependencies, or an error if the file could not be parsed.
+    fn parse_pnpm_lock(lockfile_path: &str) -> Result<NodeLockfile, Box<dyn std::error::Error>> {
         let pnpm_lock = <DependencyFile as FileParser>::read_file(lockfile_path);
         match pnpm_lock {
             Ok(pnpm_lock) => {
                 let pnpm_lock: PnpmLockFile = serde_yaml::from_str(&pnpm_lock)?;
-                let mut dependencies = Vec::new();
-                let mut seen = std::collections::HashSet::new();
+                let is_v5 = pnpm_lock
+                    .lockfile_version
+                    .as_ref()
+                    .and_then(yaml_scalar)
+                    .and_then(|version| version.parse::<f32>().ok())
+                    .is_some_and(|version| version < 6.0);
+
+                // Packages are only hoisted to node_modules/<name> with `node-linker=hoisted`.
+                let project_directory = Path::new(lockfile_path).parent().unwrap_or(Path::new(""));
+                let virtual_store_directory = pnpm_virtual_store_directory(project_directory);
+                let mut store_entries: Vec<String> = virtual_store_directory
+                    .as_ref()
+                    .and_then(|directory| std::fs::read_dir(project_directory.join(directory)).ok())
+                    .map(|entries| {
+                        entries
+                            .flatten()
+                            .map(|entry| entry.file_name().to_string_lossy().to_string())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                store_entries.sort();
+
+                // v9 moved dependencies to `snapshots` and dropped the `dev` and `optional`
+                // flags.
+                let records_scopes = pnpm_lock.snapshots.is_empty();
 
-                for package in pnpm_lock
-                    .dependencies
+                // Peer variants of a package share its `name@version` key, their dependencies
+                // are merged.
+                let mut ids: HashMap<String, String> = HashMap::new();
+                let mut modules: Vec<NodeModule> = Vec::new();
+                let mut indexes: HashMap<String, usize> = HashMap::new();
+                let mut keys: Vec<&String> = pnpm_lock
+                    .packages
                     .keys()
-                    .chain(pnpm_lock.dev_dependencies.keys())
-                    .chain(pnpm_lock.packages.keys())
-                {
-                    match <DependencyFile as FileParser>::extract_yaml_library_name(package) {
-                        formatted_name => {
-                            if formatted_name.len() != 0 && seen.insert(formatted_name.clone()) {
-                                dependencies.push(format!("node_modules/{}", formatted_name));
-                            }
+                    .chain(pnpm_lock.snapshots.keys())
+                    .collect();
+                keys.sort();
+
+                for key in &keys {
+                    let Some((name, version)) = pnpm_package_id(key, is_v5) else {
+                        continue;
+                    };
+                    let raw_key = key.trim_start_matches('/');
+                    let raw_key = raw_key.split('(').next().unwrap_or(raw_key);
+                    let package = pnpm_lock
+                        .packages
+                        .get(*key)
+                        .or_else(|| pnpm_lock.packages.get(raw_key));
+                    let name = package.and_then(|p| p.name.clone()).unwrap_or(name);
+                    let version = package.and_then(|p| p.version.clone()).unwrap_or(version);
+                    let id = format!("{name}@{version}");
+                    ids.insert(raw_key.to_string(), id.clone());
+                    // `dev` is left out for packages both projects and their development
+                    // dependencies need.
+                    let scope = package.filter(|_| records_scopes).map(|package| {
+                        match (package.dev, package.optional) {
+                            (Some(true), _) => Scope::Development,
+                            (_, true) => Scope::Optional,
+                            _ => Scope::Production,
+                        }
+                    });
+
+                    if let Some(&index) = indexes.get(&id) {
+                        // Peer variants may be installed for different projects.
+                        let module: &mut NodeModule = &mut modules[index];
+                        module.scope = module.scope.into_iter().chain(scope).min();
+                        continue;
+                    }
+                    let resolution = package.and_then(|package| package.resolution.as_ref());
+                    let resolved = resolution.and_then(|resolution| {
+                        resolution.tarball.clone().or_else(|| {
+                            Some(format!(
+                                "git+{}#{}",
+                                resolution.repo.as_ref()?,
+                                resolution.commit.as_ref()?
+                            ))
+                        })
+                    });
+                    indexes.insert(id, modules.len());
+                    modules.push(NodeModule {
+                        path: virtual_store_directory
+                            .as_ref()
+                            .and_then(|directory| {
+                                pnpm_virtual_store_path(
+                                    &name,
+                                    &version,
+                                    directory,
+                                    &store_entries,
+                                    project_directory,
+                                )
+                            })
+                            .unwrap_or(format!("node_modules/{name}")),
+                        name: Some(name),
+                        version: Some(version),
+                        integrity: resolution.and_then(|resolution| resolution.integrity.clone()),
+                        resolved,
+                        scope,
+                        ..NodeModule::default()
+                    });
+                }
+
+                for key in keys {
+                    let package = pnpm_lock
+                        .snapshots
+                        .get(key)
+                        .or_else(|| pnpm_lock.packages.get(key));
+                    let (Some(package), Some(id)) = (
+                        package,
+                        ids.get(key.trim_start_matches('/').split('(').next().unwrap_or(key)),
+                    ) else {
+                        continue;
+                    };
+                    let module = &mut modules[indexes[id]];
+                    for (dependency, reference) in package.dependencies.iter() {
+                        if let Some(dependency_id) = yaml_scalar(reference).and_then(|reference| {
+                            pnpm_dependency_id(dependency, &reference, is_v5, &ids)
+                        }) {
+                            module.dependencies.push(dependency_id);
                         }
                     }
+                    for (dependency, reference) in package.optional_dependencies.iter() {
+                        if let Some(dependency_id) = yaml_scalar(reference).and_then(|reference| {
+                            pnpm_dependency_id(dependency, &reference, is_v5, &ids)
+                        }) {
+                            module.dependencies.push(dependency_id.clone());
+                            module.optional_dependencies.push(dependency_id);
+                        }
+                    }
+                }
+
+                let mut dependencies = modules;
+                for module in dependencies.iter_mut() {
+                    module.dependencies.sort();
+                    module.dependencies.dedup();
                 }
-                Ok(dependencies)
+
+                let mut importers = pnpm_lock.importers;
+                if importers.is_empty() {
+                    importers.insert(".".to_string(), pnpm_lock.root_importer);
+                }
+                let importers = importers
+                    .into_iter()
+                    .map(|(path, project)| {
+                        let path = importer_path(&path);
+                        let mut importer = Importer {
+                            path: path.clone(),
+                            ..Importer::default()
+                        };
+                        for (dependency, reference, scope) in [
+                            (&project.dependencies, Scope::Production),
+                            (&project.optional_dependencies, Scope::Optional),
+                            (&project.dev_dependencies, Scope::Development),
+                        ]
+                        .into_iter()
+                        .flat_map(|(dependencies, scope)| {
+                            dependencies
+                                .iter()
+                                .map(move |(dependency, reference)| (dependency, reference, scope))
+                        }) {
+                            let reference = match reference {
+                                serde_yaml::Value::Mapping(_) => yaml_scalar(&reference["version"]),
+                                reference => yaml_scalar(reference),
+                            };
+                            let Some(reference) = reference else {
+                                continue;
+                            };
+                            // Workspace members are linked relative to the project.
+                            if let Some(target) = reference.strip_prefix("link:") {
+                                importer.workspace_dependencies.push(importer_path(
+                                    Path::new(&path).join(target).to_str().unwrap_or_default(),
+                                ));
+                            } else if let Some(id) =
+                                pnpm_dependency_id(dependency, &reference, is_v5, &ids)
+                            {
+                                importer.dependencies.push((id, scope));
+                            }
+                        }
+                        importer.dependencies.sort();
+                        importer.dependencies.dedup();
+                        importer
+                    })
+                    .collect::<Vec<Importer>>();
+                // v9 lockfiles no longer flag development dependencies.
+                assign_reachable_scopes(&mut dependencies, &importers);
+                Ok(NodeLockfile {
+                    modules: dependencies,
+                    importers,
+                })
             }
             Err(error) => Err(Box::new(error)),
         }
     }
 
-    fn parse_podlock(lockfile_path: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-        let podlock_file = <DependencyFile as FileParser>::read_file(lockfile_path);
-        let mut dependencies = Vec::new();
-        let mut parsed_dependencies = Vec::new();
-        match podlock_file {
-            Ok(podlock_file) => {
-                let mut is_dependencies_section = false;
-                for line in podlock_file.lines() {
-                    if line.trim() == "DEPENDENCIES:" {
-                        is_dependencies_section = true;
-                        continue;
-                    }
+    /// Parses a Podfile.lock file and returns every pod of the PODS section.
+    ///
+    /// Subspecs are folded into their root pod and the `:podspec:` / `:path:` entries of the
+    /// EXTERNAL SOURCES section are attached to the pod they describe.
+    ///
+    /// # Parameters
+    ///
+    /// * `lockfile_path` - The path to the Podfile.lock file.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec` of pods, or an error if the file could not be parsed.
+    fn parse_podlock(lockfile_path: &str) -> Result<Vec<PodLockEntry>, Box<dyn std::error::Error>> {
+        let podlock_file = <DependencyFile as FileParser>::read_file(lockfile_path)?;
+        let mut podlock: PodfileLock = serde_yaml::from_str(&podlock_file)?;
+        let mut dependencies: Vec<PodLockEntry> = Vec::new();
+        let mut indexes: HashMap<String, usize> = HashMap::new();
+        let mut dependency_names: Vec<Vec<String>> = Vec::new();
 
-                    if is_dependencies_section {
-                        if line.chars().all(|c| c.is_uppercase() || c.is_whitespace())
-                            && !line.trim().is_empty()
-                        {
-                            break; // Break if we encounter a fully capitalized word (new section)
-                        }
+        for pod in &podlock.pods {
+            let (line, pod_dependencies) = match pod {
+                PodfileLockPod::Pod(line) => (Some(line), None),
+                PodfileLockPod::PodWithDependencies(pod) => match pod.iter().next() {
+                    Some((line, pod_dependencies)) => (Some(line), Some(pod_dependencies)),
+                    None => (None, None),
+                },
+            };
 
-                        if let Some((lib_name, path)) =
-                            <DependencyFile as FileParser>::parse_podlock_dependency_line(&line)
-                        {
-                            dependencies.push((lib_name, path));
-                        }
+            let Some((lib_name, version)) =
+                line.and_then(|line| <DependencyFile as FileParser>::parse_podlock_pod_line(line))
+            else {
+                continue;
+            };
+
+            // Dependency lines are `Name (constraint)` or a bare `Name`.
+            let pod_dependency_names = pod_dependencies
+                .into_iter()
+                .flatten()
+                .filter_map(|dependency| {
+                    let dependency = dependency.trim().trim_matches('"');
+                    let name = dependency.split(" (").next().unwrap_or(dependency);
+                    name.split('/').next().map(str::to_string)
+                })
+                .filter(|name| name != &lib_name);
+
+            match indexes.get(&lib_name) {
+                // Subspecs contribute their dependencies to the root pod.
+                Some(&index) => dependency_names[index].extend(pod_dependency_names),
+                None => {
+                    let mut external_source = podlock
+                        .external_sources
+                        .remove(&lib_name)
+                        .unwrap_or_default();
+
+                    indexes.insert(lib_name.clone(), dependencies.len());
+                    dependency_names.push(pod_dependency_names.collect());
+                    dependencies.push(PodLockEntry {
+                        name: lib_name,
+                        version,
+                        podspec: external_source.remove(":podspec"),
+                        path: external_source.remove(":path"),
+                        dependencies: Vec::new(),
+                        direct: false,
+                    });
+                }
+            }
+        }
+
+        let keys: HashMap<String, String> = dependencies
+            .iter()
+            .map(|pod| (pod.name.clone(), format!("{}@{}", pod.name, pod.version)))
+            .collect();
+        for (pod, names) in dependencies.iter_mut().zip(dependency_names) {
+            pod.dependencies = names
+                .iter()
+                .filter_map(|name| keys.get(name).cloned())
+                .collect();
+            pod.dependencies.sort();
+            pod.dependencies.dedup();
+        }
+
+        let direct_names: HashSet<&str> = podlock
+            .dependencies
+            .iter()
+            .filter_map(|dependency| {
+                let dependency = dependency.trim().trim_matches('"');
+                let name = dependency.split(" (").next().unwrap_or(dependency);
+                name.split('/').next()
+            })
+            .collect();
+        for pod in dependencies.iter_mut() {
+            pod.direct = direct_names.contains(pod.name.as_str());
+        }
+
+        Ok(dependencies)
+    }
+
+    /// Parses a Gradle project and returns the Maven artifacts it ships.
+    ///
+    /// Every module included by settings.gradle(.kts) is visited: the `dependencies {}` blocks
+    /// of its Groovy or Kotlin build script are read first, then the versions resolved in its
+    /// gradle.lockfile (or legacy `gradle/dependency-locks/*.lockfile`) take precedence.
+    ///
+    /// # Parameters
+    ///
+    /// * `build_file_path` - The path to the root build.gradle, build.gradle.kts or settings file.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec` of artifacts, or an error if the project could not be read.
+    fn parse_gradle_project(
+        build_file_path: &str,
+    ) -> Result<Vec<GradleDependency>, Box<dyn std::error::Error>> {
+        let project_directory = Path::new(build_file_path)
+            .parent()
+            .ok_or("Gradle build file has no parent directory")?;
+        let read = |path: PathBuf| {
+            <DependencyFile as FileParser>::read_file(path.to_str().unwrap_or_default()).ok()
+        };
+
+        let mut module_directories = vec![project_directory.to_path_buf()];
+        if let Some(settings) = read(project_directory.join("settings.gradle"))
+            .or_else(|| read(project_directory.join("settings.gradle.kts")))
+        {
+            for module in gradle::parse_settings(&settings) {
+                module_directories.push(format_file_path!(project_directory.join(module)));
+            }
+        }
+
+        let mut variables = HashMap::new();
+        if let Some(properties) = read(project_directory.join("gradle.properties")) {
+            gradle::collect_properties(&properties, &mut variables);
+        }
+
+        let catalog_path = project_directory.join("gradle/libs.versions.toml");
+        let catalog =
+            match read(catalog_path.clone()).map(|content| VersionCatalog::parse(&content)) {
+                Some(Ok(catalog)) => catalog,
+                Some(Err(error)) => {
+                    eprintln!("Error parsing {:?}: {error}", catalog_path);
+                    VersionCatalog::default()
+                }
+                None => VersionCatalog::default(),
+            };
+
+        let mut build_scripts = Vec::new();
+        for module_directory in &module_directories {
+            for build_file in ["build.gradle", "build.gradle.kts"] {
+                if let Some(content) = read(module_directory.join(build_file)) {
+                    gradle::collect_variables(&content, &mut variables);
+                    build_scripts.push(content);
+                }
+            }
+        }
+
+        let mut dependencies: Vec<GradleDependency> = Vec::new();
+        let mut indexes: HashMap<String, usize> = HashMap::new();
+        let mut add_dependency = |dependency: GradleDependency, is_resolved: bool| match indexes
+            .get(&dependency.coordinate())
+        {
+            Some(&index) => {
+                if is_resolved || dependencies[index].version.is_empty() {
+                    dependencies[index].version = dependency.version;
+                }
+            }
+            None => {
+                indexes.insert(dependency.coordinate(), dependencies.len());
+                dependencies.push(dependency);
+            }
+        };
+
+        for build_script in &build_scripts {
+            for dependency in gradle::parse_build_script(build_script, &variables, &catalog) {
+                let dependency = GradleDependency {
+                    declared: true,
+                    ..dependency
+                };
+                add_dependency(dependency, false);
+            }
+        }
+
+        for module_directory in &module_directories {
+            let mut lockfiles = vec![module_directory.join("gradle.lockfile")];
+            if let Ok(entries) = std::fs::read_dir(module_directory.join("gradle/dependency-locks"))
+            {
+                lockfiles.extend(entries.flatten().map(|entry| entry.path()));
+            }
+
+            for lockfile in lockfiles {
+                if let Some(content) = read(lockfile) {
+                    for dependency in gradle::parse_lockfile(&content) {
+                        add_dependency(dependency, true);
                     }
                 }
-                println!("{:?}", dependencies)
             }
+        }
+
+        Ok(dependencies)
+    }
+}
+
+/// Returns the dependency file of a manager in a project directory, if there is one.
+pub(crate) fn find_dependency_file(
+    manager: ManagersArgs,
+    root_directory: &Path,
+) -> Option<PathBuf> {
+    let file_paths: &[&str] = match manager {
+        ManagersArgs::NPM => &["./package-lock.json"],
+        ManagersArgs::YARN => &["./yarn.lock"],
+        ManagersArgs::PNPM => &["./pnpm-lock.yaml"],
+        ManagersArgs::IOS => &["./ios/Podfile.lock"],
+        ManagersArgs::ANDROID => &[
+            "./android/build.gradle",
+            "./android/build.gradle.kts",
+            "./android/settings.gradle",
+            "./android/settings.gradle.kts",
+        ],
+    };
+
+    let file_path = file_paths
+        .iter()
+        .find(|file_path| DependencyFile::file_exists_in_directory(file_path, root_directory))?;
+    let mut found = Vec::new();
+    DependencyFile::update_lock_file_path(&mut found, file_path, root_directory);
+    found.pop()
+}
+
+// Files a JavaScript package manager leaves when it installs the project.
+const JAVASCRIPT_MANAGER_MARKERS: [(&str, ManagersArgs); 5] = [
+    ("node_modules/.pnpm", ManagersArgs::PNPM),
+    ("node_modules/.yarn-state.yml", ManagersArgs::YARN),
+    ("node_modules/.yarn-integrity", ManagersArgs::YARN),
+    (".pnp.cjs", ManagersArgs::YARN),
+    ("node_modules/.package-lock.json", ManagersArgs::NPM),
+];
+
+/// The JavaScript package manager of a project: the one its package.json names in
+/// `packageManager`, e.g. `pnpm@8.15.0`, or the only one that left its marker on install.
+fn javascript_manager(root_directory: &Path) -> Option<ManagersArgs> {
+    let declared = read_package_json(&root_directory.join("package.json"))
+        .and_then(|package_json| package_json.package_manager);
+    if let Some(declared) = declared {
+        match declared.split('@').next().unwrap_or_default() {
+            "npm" => return Some(ManagersArgs::NPM),
+            "yarn" => return Some(ManagersArgs::YARN),
+            "pnpm" => return Some(ManagersArgs::PNPM),
             _ => (),
         }
-        return Ok(parsed_dependencies);
     }
 
-    fn parse_settings_graddle(lockfile_path: &str) -> io::Result<()> {
-        let content = <DependencyFile as FileParser>::read_file(lockfile_path);
-        Result::from(Ok(println!("Here 5 parse_package_lock: {:?} ", content)))
+    let mut installed: Vec<ManagersArgs> = JAVASCRIPT_MANAGER_MARKERS
+        .iter()
+        .filter(|(marker, _)| root_directory.join(marker).exists())
+        .map(|(_, manager)| *manager)
+        .collect();
+    installed.dedup();
+    match installed.as_slice() {
+        [manager] => Some(*manager),
+        _ => None,
     }
 }
 
+/// Finds the dependency files of the given managers, or of every manager the project uses
+/// when none is given. Of several JavaScript lockfiles, only the one of the manager the
+/// project uses is detected, all of them when that is unknown.
 pub(crate) fn handle_dependencies_files(
-    manager: ManagersArgs,
-    root_directory: &PathBuf,
-) -> Vec<PathBuf> {
-    let mut lockfilepaths: Vec<PathBuf> = Vec::new();
-
-    let file_path = match manager {
-        ManagersArgs::NPM => "./package-lock.json".to_string(),
-        ManagersArgs::YARN => "./yarn.lock".to_string(),
-        ManagersArgs::PNPM => "./pnpm-lock.yaml".to_string(),
-        ManagersArgs::IOS => "./ios/Podfile.lock".to_string(),
-        ManagersArgs::ANDROID => "./android/build.gradle".to_string(),
+    managers: &[ManagersArgs],
+    root_directory: &Path,
+) -> Vec<(ManagersArgs, PathBuf)> {
+    let detect = managers.is_empty();
+    let managers = if detect {
+        ManagersArgs::value_variants()
+    } else {
+        managers
     };
+    let mut lockfilepaths: Vec<(ManagersArgs, PathBuf)> = Vec::new();
 
-    if DependencyFile::file_exists_in_directory(&file_path, root_directory) {
-        lockfilepaths.push(PathBuf::from(format_file_path!(
-            root_directory.join(file_path)
-        )));
+    for &manager in managers {
+        match find_dependency_file(manager, root_directory) {
+            Some(path) => lockfilepaths.push((manager, path)),
+            None if !detect => eprintln!(
+                "No {} dependency file found in {:?}",
+                manager
+                    .to_possible_value()
+                    .map_or(String::new(), |value| value.get_name().to_string()),
+                root_directory
+            ),
+            None => (),
+        }
     }
 
+    if detect && lockfilepaths.is_empty() {
+        eprintln!("No dependency file found in {:?}", root_directory);
+    }
+    let is_javascript = |manager: &ManagersArgs| {
+        matches!(
+            manager,
+            ManagersArgs::NPM | ManagersArgs::YARN | ManagersArgs::PNPM
+        )
+    };
+    if detect
+        && lockfilepaths
+            .iter()
+            .filter(|(manager, _)| is_javascript(manager))
+            .count()
+            > 1
+    {
+        match javascript_manager(root_directory)
+            .filter(|used| lockfilepaths.iter().any(|(manager, _)| manager == used))
+        {
+            Some(used) => lockfilepaths.retain(|(manager, path)| {
+                let skipped = is_javascript(manager) && *manager != used;
+                if skipped {
+                    eprintln!(
+                        "Skipping {:?}, the project is installed with another package manager, pass --manager to parse it too",
+                        path
+                    );
+                }
+                !skipped
+            }),
+            None => eprintln!(
+                "Merging the JavaScript lockfiles of {:?}, set `packageManager` in its package.json to only read one",
+                root_directory
+            ),
+        }
+    }
     lockfilepaths
 }
 
 pub(crate) fn parse_lock_file(
     manager: ManagersArgs,
-    root: &PathBuf,
+    root: &Path,
     lockfile_path: &str,
-) -> Vec<ParsedPackageJson> {
+) -> ParsedLockfile {
     let mut parsed_dependencies: Vec<ParsedPackageJson> = Vec::new();
-    let _ = match manager {
+    let mut importers: Vec<Importer> = Vec::new();
+    match manager {
         ManagersArgs::NPM => {
             let dependencies = DependencyFile::parse_package_lock(lockfile_path);
             match dependencies {
                 Ok(package_lock) => {
-                    let found = DependencyFile::get_node_module_package_info(package_lock, &root);
+                    let found =
+                        DependencyFile::get_node_module_package_info(package_lock.modules, root);
                     parsed_dependencies = found;
+                    importers = package_lock.importers;
                 }
                 Err(error) => eprintln!("{error}"),
             }
@@ -442,8 +2631,10 @@ pub(crate) fn parse_lock_file(
             let dependencies = DependencyFile::parse_yarn_lock(lockfile_path);
             match dependencies {
                 Ok(package_lock) => {
-                    let found = DependencyFile::get_node_module_package_info(package_lock, &root);
+                    let found =
+                        DependencyFile::get_node_module_package_info(package_lock.modules, root);
                     parsed_dependencies = found;
+                    importers = package_lock.importers;
                 }
                 Err(error) => eprintln!("{error}"),
             }
@@ -452,22 +2643,423 @@ pub(crate) fn parse_lock_file(
             let dependencies = DependencyFile::parse_pnpm_lock(lockfile_path);
             match dependencies {
                 Ok(package_lock) => {
-                    let found = DependencyFile::get_node_module_package_info(package_lock, &root);
+                    let found =
+                        DependencyFile::get_node_module_package_info(package_lock.modules, root);
                     parsed_dependencies = found;
+                    importers = package_lock.importers;
                 }
                 Err(error) => eprintln!("{error}"),
             }
         }
         ManagersArgs::IOS => {
-            let podlock_file = DependencyFile::parse_settings_graddle(lockfile_path);
-
             let dependencies = DependencyFile::parse_podlock(lockfile_path);
-            ()
+            let ios_directory = Path::new(lockfile_path).parent().unwrap_or(root);
+            match dependencies {
+                Ok(pods) => {
+                    importers.push(Importer {
+                        path: relative_to_root(ios_directory, root),
+                        ecosystem: Ecosystem::CocoaPods,
+                        dependencies: pods
+                            .iter()
+                            .filter(|pod| pod.direct)
+                            .map(|pod| (format!("{}@{}", pod.name, pod.version), Scope::Production))
+                            .collect(),
+                        ..Importer::default()
+                    });
+                    let found = DependencyFile::get_pod_package_info(pods, ios_directory, root);
+                    parsed_dependencies = found;
+                }
+                Err(error) => eprintln!("{error}"),
+            }
         }
         ManagersArgs::ANDROID => {
-            let _ = DependencyFile::parse_settings_graddle(lockfile_path);
-            ()
+            let dependencies = DependencyFile::parse_gradle_project(lockfile_path);
+            let android_directory = Path::new(lockfile_path).parent().unwrap_or(root);
+            match dependencies {
+                Ok(artifacts) => {
+                    let declared: Vec<bool> = artifacts.iter().map(|a| a.declared).collect();
+                    let found = DependencyFile::get_gradle_package_info(artifacts, root);
+                    // Versions are only known once resolved from the cache, the declared
+                    // artifacts come first.
+                    importers.push(Importer {
+                        path: relative_to_root(android_directory, root),
+                        ecosystem: Ecosystem::Maven,
+                        dependencies: found
+                            .iter()
+                            .zip(declared)
+                            .filter(|(_, declared)| *declared)
+                            .map(|(package, _)| (package.key(), Scope::Production))
+                            .collect(),
+                        ..Importer::default()
+                    });
+                    parsed_dependencies = found;
+                }
+                Err(error) => eprintln!("{error}"),
+            }
         }
     };
-    parsed_dependencies
+    for package in parsed_dependencies.iter_mut() {
+        read_license_files(package, root);
+    }
+    // Copies are folded with those of the other lockfiles, their scopes settled together.
+    ParsedLockfile {
+        packages: parsed_dependencies,
+        importers,
+    }
+}
+
+impl ParsedPackageJson {
+    /// The declared license, or the one detected from the license file when the declaration
+    /// names no known license.
+    pub(crate) fn known_license(&self) -> Option<&LicenseExpression> {
+        self.license_expression.as_ref().or(self
+            .detected_license
+            .as_ref()
+            .map(|detected| &detected.license))
+    }
+
+    /// Identifies a package in the report, several copies of one version share the same key.
+    pub(crate) fn key(&self) -> String {
+        if self.version.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}@{}", self.name, self.version)
+        }
+    }
+
+    /// The scope of the package, production when no lockfile told it.
+    pub(crate) fn scope(&self) -> Scope {
+        self.scope.unwrap_or_default()
+    }
+
+    /// The npm scope or Maven group of the package, if any.
+    pub(crate) fn group(&self) -> Option<&str> {
+        match self.ecosystem {
+            Ecosystem::Npm => self
+                .name
+                .split_once('/')
+                .filter(|(scope, _)| scope.starts_with('@'))
+                .map(|(scope, _)| scope),
+            Ecosystem::Maven => self.name.split_once(':').map(|(group, _)| group),
+            Ecosystem::CocoaPods => None,
+        }
+    }
+
+    /// The name without its npm scope or Maven group.
+    pub(crate) fn short_name(&self) -> &str {
+        match (self.ecosystem, self.group()) {
+            (Ecosystem::Npm, Some(_)) => self.name.split_once('/').map_or("", |(_, name)| name),
+            (Ecosystem::Maven, Some(_)) => self.name.split_once(':').map_or("", |(_, name)| name),
+            _ => &self.name,
+        }
+    }
+
+    /// The package URL, e.g. `pkg:npm/%40babel/core@7.23.2` or `pkg:maven/androidx.core/core@1.9.0`.
+    pub(crate) fn purl(&self) -> String {
+        let mut purl = format!("pkg:{}/", self.ecosystem.as_str());
+        if let Some(group) = self.group() {
+            purl.push_str(&percent_encode(group));
+            purl.push('/');
+        }
+        purl.push_str(&percent_encode(self.short_name()));
+        if !self.version.is_empty() {
+            purl.push('@');
+            purl.push_str(&percent_encode(&self.version));
+        }
+        purl
+    }
+
+    /// Decodes the Subresource Integrity value of the lockfile into `(algorithm, hex digest)`
+    /// pairs, e.g. `("SHA-512", "9f86d0...")`.
+    pub(crate) fn hashes(&self) -> Vec<(&'static str, String)> {
+        self.integrity
+            .split_whitespace()
+            .filter_map(|integrity| {
+                let (algorithm, digest) = integrity.split_once('-')?;
+                let algorithm = match algorithm {
+                    "sha1" => "SHA-1",
+                    "sha256" => "SHA-256",
+                    "sha384" => "SHA-384",
+                    "sha512" => "SHA-512",
+                    _ => return None,
+                };
+                let digest = BASE64_STANDARD.decode(digest).ok()?;
+                let hex = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+                Some((algorithm, hex))
+            })
+            .collect()
+    }
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'.' | b'-' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+/// Fills in what the entry of a package lacks from a copy read from another lockfile.
+fn fill_missing_metadata(package: &mut ParsedPackageJson, copy: &ParsedPackageJson) {
+    if package.license.is_empty() && package.detected_license.is_none() {
+        package.license = copy.license.clone();
+        package.license_expression = copy.license_expression.clone();
+        package.detected_license = copy.detected_license.clone();
+    }
+    if package.license_text.is_empty() && package.notice_text.is_empty() {
+        package.copyrights = copy.copyrights.clone();
+    }
+    for (field, value) in [
+        (&mut package.license_url, &copy.license_url),
+        (
+            &mut package.declared_license_url,
+            &copy.declared_license_url,
+        ),
+        (&mut package.license_text, &copy.license_text),
+        (&mut package.notice_url, &copy.notice_url),
+        (&mut package.notice_text, &copy.notice_text),
+        (&mut package.description, &copy.description),
+        (&mut package.repository_url, &copy.repository_url),
+        (&mut package.homepage, &copy.homepage),
+        (&mut package.author, &copy.author),
+        (&mut package.resolved, &copy.resolved),
+        (&mut package.integrity, &copy.integrity),
+    ] {
+        if field.is_empty() {
+            *field = value.clone();
+        }
+    }
+}
+
+/// Folds copies of the same name and version installed at different paths into one entry,
+/// while keeping every distinct version. The scope a lockfile gives wins over an unknown one,
+/// and what one lockfile records fills in for another, e.g. the licenses of package-lock.json.
+pub(crate) fn merge_installed_copies(packages: Vec<ParsedPackageJson>) -> Vec<ParsedPackageJson> {
+    let mut merged: Vec<ParsedPackageJson> = Vec::new();
+    let mut indexes: HashMap<(Ecosystem, String), usize> = HashMap::new();
+
+    for package in packages {
+        match indexes.get(&(package.ecosystem, package.key())) {
+            Some(&index) => {
+                fill_missing_metadata(&mut merged[index], &package);
+                merged[index].paths.extend(package.paths);
+                merged[index].dependencies.extend(package.dependencies);
+                merged[index].scope = match (merged[index].scope, package.scope) {
+                    (Some(scope), Some(other)) => Some(scope.min(other)),
+                    (scope, other) => scope.or(other),
+                };
+            }
+            None => {
+                indexes.insert((package.ecosystem, package.key()), merged.len());
+                merged.push(package);
+            }
+        }
+    }
+
+    // Entries no lockfile gives a scope are kept as production ones rather than dropped from
+    // reports.
+    for package in merged.iter_mut() {
+        package.scope.get_or_insert(Scope::Production);
+        package.paths.sort();
+        package.paths.dedup();
+        package.dependencies.sort();
+        package.dependencies.dedup();
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn license_id(package_json: &str) -> Option<String> {
+        serde_json::from_str::<PackageJson>(package_json)
+            .unwrap()
+            .license_id()
+    }
+
+    #[test]
+    fn reads_license_arrays_as_alternatives() {
+        assert_eq!(
+            license_id(r#"{"licenses": [{"type": "MIT"}, {"type": "Apache-2.0"}]}"#),
+            Some("(MIT OR Apache-2.0)".to_string())
+        );
+        assert_eq!(
+            license_id(r#"{"licenses": [{"type": "MIT", "url": "https://mit-license.org"}]}"#),
+            Some("MIT".to_string())
+        );
+        assert_eq!(
+            license_id(r#"{"license": {"type": "ISC"}}"#),
+            Some("ISC".to_string())
+        );
+        assert_eq!(
+            license_id(r#"{"license": "MIT", "licenses": [{"type": "GPL-2.0"}]}"#),
+            Some("MIT".to_string())
+        );
+    }
+
+    #[test]
+    fn splits_pnpm_package_keys() {
+        let expected = Some(("@babel/core".to_string(), "7.23.3".to_string()));
+        // v5, v6 and v9 keys.
+        assert_eq!(
+            pnpm_package_id("/@babel/core/7.23.3_supports-color@8.1.1", true),
+            expected
+        );
+        assert_eq!(
+            pnpm_package_id("/@babel/core@7.23.3(supports-color@8.1.1)", false),
+            expected
+        );
+        assert_eq!(
+            pnpm_package_id("@babel/core@7.23.3(supports-color@8.1.1)", false),
+            expected
+        );
+        assert_eq!(
+            pnpm_package_id("/chalk/2.4.2", true),
+            Some(("chalk".to_string(), "2.4.2".to_string()))
+        );
+    }
+
+    #[test]
+    fn resolves_pnpm_dependencies_without_peer_suffixes() {
+        let ids = HashMap::new();
+        assert_eq!(
+            pnpm_dependency_id("@babel/core", "7.23.3_supports-color@8.1.1", true, &ids),
+            Some("@babel/core@7.23.3".to_string())
+        );
+        assert_eq!(
+            pnpm_dependency_id(
+                "@babel/helper-module-transforms",
+                "7.23.3(@babel/core@7.23.3)",
+                false,
+                &ids
+            ),
+            Some("@babel/helper-module-transforms@7.23.3".to_string())
+        );
+        // Aliases point to the package they install.
+        assert_eq!(
+            pnpm_dependency_id("string-width-cjs", "/string-width@4.2.3", false, &ids),
+            Some("string-width@4.2.3".to_string())
+        );
+        assert_eq!(
+            pnpm_dependency_id("string-width-cjs", "string-width@4.2.3", false, &ids),
+            Some("string-width@4.2.3".to_string())
+        );
+        assert_eq!(
+            pnpm_dependency_id("lib", "link:packages/lib", false, &ids),
+            None
+        );
+    }
+
+    const YARN_BERRY_LOCK: &str = r#"
+__metadata:
+  version: 8
+  cacheKey: 10c0
+
+"app@workspace:.":
+  version: 0.0.0-use.local
+  resolution: "app@workspace:."
+  dependencies:
+    lib: "workspace:packages/lib"
+    resolve: "patch:resolve@npm%3A^1.22.1#~builtin<compat/resolve>"
+    string-width-cjs: "npm:string-width@^4.2.0"
+  languageName: unknown
+  linkType: soft
+
+"lib@workspace:packages/lib":
+  version: 0.0.0-use.local
+  resolution: "lib@workspace:packages/lib"
+  dependencies:
+    resolve: "npm:^1.22.1"
+  languageName: unknown
+  linkType: soft
+
+"resolve@patch:resolve@npm%3A^1.22.1#~builtin<compat/resolve>":
+  version: 1.22.8
+  resolution: "resolve@patch:resolve@npm%3A1.22.8#~builtin<compat/resolve>::version=1.22.8&hash=c3c19d"
+  languageName: node
+  linkType: hard
+
+"resolve@npm:^1.22.1":
+  version: 1.22.8
+  resolution: "resolve@npm:1.22.8"
+  languageName: node
+  linkType: hard
+
+"string-width-cjs@npm:string-width@^4.2.0":
+  version: 4.2.3
+  resolution: "string-width@npm:4.2.3"
+  languageName: node
+  linkType: hard
+"#;
+
+    #[test]
+    fn parses_yarn_berry_resolutions() {
+        let lockfile = parse_yarn_berry_lock(YARN_BERRY_LOCK, Path::new("/nonexistent")).unwrap();
+
+        let modules: Vec<(&str, Option<&str>, Option<&str>)> = lockfile
+            .modules
+            .iter()
+            .map(|module| {
+                (
+                    module.path.as_str(),
+                    module.version.as_deref(),
+                    module.resolved.as_deref(),
+                )
+            })
+            .collect();
+        // The patched copy of `resolve` is reported as the package it patches.
+        assert_eq!(
+            modules,
+            vec![
+                (
+                    "node_modules/resolve",
+                    Some("1.22.8"),
+                    Some("resolve@npm:1.22.8")
+                ),
+                (
+                    "node_modules/string-width-cjs",
+                    Some("4.2.3"),
+                    Some("string-width@npm:4.2.3")
+                ),
+            ]
+        );
+        assert_eq!(lockfile.modules[1].name.as_deref(), Some("string-width"));
+
+        let mut importers: Vec<(&str, Vec<&str>, Vec<&str>)> = lockfile
+            .importers
+            .iter()
+            .map(|importer| {
+                (
+                    importer.path.as_str(),
+                    importer
+                        .dependencies
+                        .iter()
+                        .map(|(key, _)| key.as_str())
+                        .collect(),
+                    importer
+                        .workspace_dependencies
+                        .iter()
+                        .map(String::as_str)
+                        .collect(),
+                )
+            })
+            .collect();
+        importers.sort();
+        assert_eq!(
+            importers,
+            vec![
+                (
+                    "",
+                    vec!["resolve@1.22.8", "string-width@4.2.3"],
+                    vec!["packages/lib"]
+                ),
+                ("packages/lib", vec!["resolve@1.22.8"], vec![]),
+            ]
+        );
+    }
 }
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use serde_json::Value;
+use zip::ZipArchive;
+
+/// Where Yarn Plug'n'Play keeps each package, read from `.pnp.data.json` or `.pnp.cjs`.
+pub(crate) struct PnpManifest {
+    // `name@reference` locator to the package directory, relative to the project root.
+    locations: HashMap<String, String>,
+}
+
+impl PnpManifest {
+    /// Reads the manifest of a project installed with PnP, if it is one.
+    pub(crate) fn from_project(root_directory: &Path) -> Option<PnpManifest> {
+        let data = match fs::read_to_string(root_directory.join(".pnp.data.json")) {
+            Ok(data) => data,
+            Err(_) => {
+                let runtime = fs::read_to_string(root_directory.join(".pnp.cjs"))
+                    .or_else(|_| fs::read_to_string(root_directory.join(".pnp.js")))
+                    .ok()?;
+                runtime_state(&runtime)?
+            }
+        };
+        let state: Value = match serde_json::from_str(&data) {
+            Ok(state) => state,
+            Err(error) => {
+                eprintln!("Error parsing the Plug'n'Play manifest: {error}");
+                return None;
+            }
+        };
+
+        // `[[name, [[reference, {packageLocation, ...}], ...]], ...]`, the top level package
+        // has neither name nor reference.
+        let mut locations = HashMap::new();
+        for package in state["packageRegistryData"].as_array()? {
+            let Some(name) = package[0].as_str() else {
+                continue;
+            };
+            for version in package[1].as_array().into_iter().flatten() {
+                let (Some(reference), Some(location)) =
+                    (version[0].as_str(), version[1]["packageLocation"].as_str())
+                else {
+                    continue;
+                };
+                locations.insert(format!("{name}@{reference}"), location.to_string());
+            }
+        }
+        Some(PnpManifest { locations })
+    }
+
+    /// Returns the directory of a package from its locator, e.g. `chalk@npm:2.4.2`.
+    pub(crate) fn location(&self, locator: &str) -> Option<&str> {
+        self.locations.get(locator).map(String::as_str)
+    }
+}
+
+/// Extracts the JSON state `.pnp.cjs` embeds as a single quoted string literal.
+fn runtime_state(runtime: &str) -> Option<String> {
+    let start = runtime
+        .find("JSON.parse('")
+        .map(|index| index + "JSON.parse(".len())
+        .or_else(|| {
+            let index = runtime.find("RAW_RUNTIME_STATE =")?;
+            runtime[index..].find('\'').map(|quote| index + quote)
+        })?;
+
+    let mut state = String::new();
+    let mut chars = runtime[start + 1..].chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => return Some(state),
+            '\\' => match chars.next()? {
+                // Line continuations.
+                '\n' => (),
+                '\r' => {
+                    chars.next();
+                }
+                'n' => state.push('\n'),
+                't' => state.push('\t'),
+                'r' => state.push('\r'),
+                escaped => state.push(escaped),
+            },
+            _ => state.push(c),
+        }
+    }
+    None
+}
+
+// Zip archives packages are read from, yarn cache archives and Maven jars and aars.
+const ARCHIVE_EXTENSIONS: [&str; 3] = [".zip/", ".jar/", ".aar/"];
+
+/// Splits a location inside a zip archive, e.g.
+/// `.yarn/cache/chalk-npm-2.4.2-3ea16dd91e-ec3661d38f.zip/node_modules/chalk/`, into the
+/// archive path and the directory inside it.
+pub(crate) fn split_zip_path(location: &str) -> Option<(&str, &str)> {
+    let index = ARCHIVE_EXTENSIONS
+        .iter()
+        .filter_map(|extension| location.find(extension))
+        .min()?
+        + ".zip".len();
+    Some((&location[..index], &location[index + 1..]))
+}
+
+/// Returns the names of the files directly inside `directory` of a zip archive.
+pub(crate) fn zip_file_names(archive_path: &Path, directory: &str) -> Vec<String> {
+    let Some(archive) = File::open(archive_path)
+        .ok()
+        .and_then(|file| ZipArchive::new(file).ok())
+    else {
+        return Vec::new();
+    };
+    archive
+        .file_names()
+        .filter_map(|name| name.strip_prefix(directory))
+        .filter(|name| !name.is_empty() && !name.contains('/'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Reads a text file stored in a zip archive.
+pub(crate) fn read_zip_entry(archive_path: &Path, name: &str) -> Option<String> {
+    let mut archive = ZipArchive::new(File::open(archive_path).ok()?).ok()?;
+    let mut entry = archive.by_name(name).ok()?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content).ok()?;
+    Some(content)
+}
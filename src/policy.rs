@@ -0,0 +1,451 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use serde_derive::Deserialize;
+
+use crate::license::LicenseExpression;
+use crate::parser::ParsedPackageJson;
+
+// Strong copyleft licenses, denied unless the policy lists its own.
+const DEFAULT_DENIED: [&str; 2] = ["GPL*", "AGPL*"];
+
+/// Which licenses a project accepts, read from a JSON or YAML file.
+#[derive(Debug, Deserialize)]
+pub(crate) struct Policy {
+    // SPDX ids accepted, every id that is not denied when empty. A trailing `*` matches any
+    // suffix, e.g. `BSD-*`.
+    #[serde(default)]
+    allowed: Vec<String>,
+    #[serde(default = "default_denied")]
+    denied: Vec<String>,
+    #[serde(default)]
+    exceptions: Vec<PolicyException>,
+}
+
+/// A package accepted whatever its license, e.g. after a legal review.
+#[derive(Debug, Deserialize)]
+struct PolicyException {
+    // `name` or `name@version`.
+    package: String,
+    // The license the exception was granted for, it no longer applies once it changes.
+    license: Option<String>,
+    justification: String,
+}
+
+fn default_denied() -> Vec<String> {
+    DEFAULT_DENIED.iter().map(|id| id.to_string()).collect()
+}
+
+impl Default for Policy {
+    fn default() -> Policy {
+        Policy {
+            allowed: Vec::new(),
+            denied: default_denied(),
+            exceptions: Vec::new(),
+        }
+    }
+}
+
+enum Verdict<'a> {
+    Allowed,
+    // The ids failing the policy.
+    Denied(Vec<String>),
+    NotAllowed(Vec<String>),
+    Unknown,
+    Excepted(&'a PolicyException),
+}
+
+/// The outcome of checking every package against a policy.
+pub(crate) struct PolicyReport<'a> {
+    checked: usize,
+    denied: Vec<(&'a ParsedPackageJson, Vec<String>)>,
+    not_allowed: Vec<(&'a ParsedPackageJson, Vec<String>)>,
+    unknown: Vec<&'a ParsedPackageJson>,
+    excepted: Vec<(&'a ParsedPackageJson, &'a PolicyException)>,
+    unused_exceptions: Vec<&'a PolicyException>,
+}
+
+fn matches_pattern(pattern: &str, id: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => id
+            .to_ascii_lowercase()
+            .starts_with(&prefix.to_ascii_lowercase()),
+        None => pattern.eq_ignore_ascii_case(id),
+    }
+}
+
+// Ids are listed the way packages declare them once parsed, e.g. `Apache 2.0` as `Apache-2.0`.
+fn canonical_pattern(pattern: &str) -> String {
+    if pattern.ends_with('*') {
+        return pattern.to_string();
+    }
+    match LicenseExpression::parse(pattern) {
+        Some(expression @ (LicenseExpression::License(_) | LicenseExpression::With(_, _))) => {
+            expression.to_string()
+        }
+        _ => pattern.to_string(),
+    }
+}
+
+impl Policy {
+    /// Reads a policy file, YAML being a superset of JSON both are read the same way.
+    pub(crate) fn from_file(path: &Path) -> Result<Policy, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let mut policy: Policy = serde_yaml::from_str(&content)?;
+        for pattern in policy.allowed.iter_mut().chain(policy.denied.iter_mut()) {
+            *pattern = canonical_pattern(pattern);
+        }
+        Ok(policy)
+    }
+
+    /// Evaluates an expression: OR needs one acceptable operand, AND needs all of them.
+    fn evaluate(&self, expression: &LicenseExpression) -> Verdict<'_> {
+        match expression {
+            LicenseExpression::License(id) => self.evaluate_id(id, None),
+            LicenseExpression::With(id, exception) => self.evaluate_id(id, Some(exception)),
+            LicenseExpression::Or(operands) => {
+                let verdicts: Vec<Verdict> = operands.iter().map(|o| self.evaluate(o)).collect();
+                if verdicts.iter().any(|v| matches!(v, Verdict::Allowed)) {
+                    return Verdict::Allowed;
+                }
+                combine(verdicts)
+            }
+            LicenseExpression::And(operands) => {
+                combine(operands.iter().map(|o| self.evaluate(o)).collect())
+            }
+        }
+    }
+
+    // Exact ids win over `*` patterns so `GPL-2.0-only WITH Classpath-exception-2.0` can be
+    // allowed while `GPL*` is denied, and the id with its exception wins over the bare id.
+    fn evaluate_id(&self, id: &str, exception: Option<&String>) -> Verdict<'_> {
+        let with_exception = exception.map(|exception| format!("{id} WITH {exception}"));
+        let names: Vec<&str> = with_exception
+            .iter()
+            .map(String::as_str)
+            .chain([id])
+            .collect();
+        let name = names[0].to_string();
+
+        for wildcard in [false, true] {
+            let listed = |patterns: &Vec<String>, name: &str| {
+                patterns
+                    .iter()
+                    .filter(|pattern| pattern.ends_with('*') == wildcard)
+                    .any(|pattern| matches_pattern(pattern, name))
+            };
+            for candidate in &names {
+                if listed(&self.denied, candidate) {
+                    return Verdict::Denied(vec![name]);
+                }
+                if listed(&self.allowed, candidate) {
+                    return Verdict::Allowed;
+                }
+            }
+        }
+        match self.allowed.is_empty() {
+            true => Verdict::Allowed,
+            false => Verdict::NotAllowed(vec![name]),
+        }
+    }
+
+    fn exception_for(&self, package: &ParsedPackageJson) -> Option<&PolicyException> {
+        self.exceptions.iter().find(|exception| {
+            (exception.package == package.name || exception.package == package.key())
+                && exception.license.as_ref().is_none_or(|license| {
+                    match LicenseExpression::parse(license) {
+                        Some(expression) => package.license_expression == Some(expression),
+                        None => license.trim() == package.license.trim(),
+                    }
+                })
+        })
+    }
+
+    fn verdict<'a>(&'a self, package: &ParsedPackageJson) -> Verdict<'a> {
+        if let Some(exception) = self.exception_for(package) {
+            return Verdict::Excepted(exception);
+        }
+        match package.known_license() {
+            Some(expression) => self.evaluate(expression),
+            None => Verdict::Unknown,
+        }
+    }
+
+    /// Checks the license of every package.
+    pub(crate) fn check<'a>(&'a self, packages: &'a [ParsedPackageJson]) -> PolicyReport<'a> {
+        let mut report = PolicyReport {
+            checked: packages.len(),
+            denied: Vec::new(),
+            not_allowed: Vec::new(),
+            unknown: Vec::new(),
+            excepted: Vec::new(),
+            unused_exceptions: Vec::new(),
+        };
+        let mut used_exceptions = HashSet::new();
+
+        for package in packages {
+            match self.verdict(package) {
+                Verdict::Allowed => (),
+                Verdict::Denied(ids) => report.denied.push((package, ids)),
+                Verdict::NotAllowed(ids) => report.not_allowed.push((package, ids)),
+                Verdict::Unknown => report.unknown.push(package),
+                Verdict::Excepted(exception) => {
+                    used_exceptions.insert(&exception.package);
+                    report.excepted.push((package, exception));
+                }
+            }
+        }
+        report.denied.sort_by_key(|(package, _)| describe(package));
+        report
+            .not_allowed
+            .sort_by_key(|(package, _)| describe(package));
+        report.unknown.sort_by_key(|package| describe(package));
+        report
+            .excepted
+            .sort_by_key(|(package, _)| describe(package));
+        report.unused_exceptions = self
+            .exceptions
+            .iter()
+            .filter(|exception| !used_exceptions.contains(&exception.package))
+            .collect();
+        report
+    }
+}
+
+/// Merges the verdicts of operands that are not all allowed, denial winning over the others.
+fn combine(verdicts: Vec<Verdict<'_>>) -> Verdict<'_> {
+    let mut denied = Vec::new();
+    let mut not_allowed = Vec::new();
+    let mut unknown = false;
+    for verdict in verdicts {
+        match verdict {
+            Verdict::Denied(ids) => denied.extend(ids),
+            Verdict::NotAllowed(ids) => not_allowed.extend(ids),
+            Verdict::Unknown => unknown = true,
+            Verdict::Allowed | Verdict::Excepted(_) => (),
+        }
+    }
+    if !denied.is_empty() {
+        Verdict::Denied(denied)
+    } else if !not_allowed.is_empty() {
+        Verdict::NotAllowed(not_allowed)
+    } else if unknown {
+        Verdict::Unknown
+    } else {
+        Verdict::Allowed
+    }
+}
+
+fn describe(package: &ParsedPackageJson) -> String {
+    format!("{} ({})", package.key(), package.ecosystem.as_str())
+}
+
+// Verdicts on a license read from a file rather than declared say so.
+fn detected_from(package: &ParsedPackageJson) -> String {
+    match (&package.license_expression, &package.detected_license) {
+        (None, Some(detected)) => format!(
+            " (detected in {}, confidence {})",
+            detected.file, detected.confidence
+        ),
+        _ => String::new(),
+    }
+}
+
+impl PolicyReport<'_> {
+    /// Whether no package fails the policy.
+    pub(crate) fn passes(&self) -> bool {
+        self.denied.is_empty() && self.not_allowed.is_empty() && self.unknown.is_empty()
+    }
+
+    /// Lists the packages failing the policy, then the exceptions applied.
+    pub(crate) fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut section = |title: &str, entries: Vec<String>| {
+            if entries.is_empty() {
+                return;
+            }
+            lines.push(format!("{title}:"));
+            lines.extend(entries.into_iter().map(|entry| format!("  {entry}")));
+        };
+
+        section(
+            "Denied licenses",
+            self.denied
+                .iter()
+                .map(|(package, ids)| {
+                    format!(
+                        "{}: {}{}",
+                        describe(package),
+                        ids.join(", "),
+                        detected_from(package)
+                    )
+                })
+                .collect(),
+        );
+        section(
+            "Licenses not in the allowed list",
+            self.not_allowed
+                .iter()
+                .map(|(package, ids)| {
+                    format!(
+                        "{}: {}{}",
+                        describe(package),
+                        ids.join(", "),
+                        detected_from(package)
+                    )
+                })
+                .collect(),
+        );
+        section(
+            "Unknown licenses",
+            self.unknown
+                .iter()
+                .map(|package| match package.license.trim() {
+                    "" => format!("{}: no license declared", describe(package)),
+                    license => format!("{}: {license}", describe(package)),
+                })
+                .collect(),
+        );
+        section(
+            "Exceptions",
+            self.excepted
+                .iter()
+                .map(|(package, exception)| {
+                    format!(
+                        "{}: {} ({})",
+                        describe(package),
+                        package.license,
+                        exception.justification
+                    )
+                })
+                .collect(),
+        );
+        section(
+            "Exceptions matching no package",
+            self.unused_exceptions
+                .iter()
+                .map(|exception| exception.package.clone())
+                .collect(),
+        );
+
+        lines.push(format!(
+            "{} packages checked: {} denied, {} not allowed, {} unknown, {} excepted",
+            self.checked,
+            self.denied.len(),
+            self.not_allowed.len(),
+            self.unknown.len(),
+            self.excepted.len()
+        ));
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(name: &str, license: &str) -> ParsedPackageJson {
+        ParsedPackageJson {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            license: license.to_string(),
+            license_expression: LicenseExpression::parse(license),
+            ..ParsedPackageJson::default()
+        }
+    }
+
+    fn policy(yaml: &str) -> Policy {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn names<'a>(packages: impl IntoIterator<Item = &'a ParsedPackageJson>) -> Vec<&'a str> {
+        packages
+            .into_iter()
+            .map(|package| package.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn denies_copyleft_licenses_by_default() {
+        let packages = [
+            package("a", "MIT"),
+            package("b", "GPL-3.0-only"),
+            package("c", "MIT OR GPL-3.0-only"),
+            package("d", "MIT AND GPL-2.0-or-later"),
+        ];
+        let policy = Policy::default();
+        let report = policy.check(&packages);
+        assert!(!report.passes());
+        assert_eq!(report.checked, 4);
+        let denied: Vec<(&str, &[String])> = report
+            .denied
+            .iter()
+            .map(|(package, ids)| (package.name.as_str(), ids.as_slice()))
+            .collect();
+        assert_eq!(
+            denied,
+            vec![
+                ("b", ["GPL-3.0-only".to_string()].as_slice()),
+                ("d", ["GPL-2.0-or-later".to_string()].as_slice()),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_licenses_not_allowed() {
+        let packages = [package("a", "MIT"), package("b", "ISC")];
+        let policy = policy("allowed: [MIT, BSD-*]");
+        let report = policy.check(&packages);
+        assert!(!report.passes());
+        assert_eq!(names(report.not_allowed.iter().map(|(p, _)| *p)), ["b"]);
+    }
+
+    #[test]
+    fn reports_unknown_licenses() {
+        let packages = [package("a", "SEE LICENSE IN LICENSE.txt"), package("b", "")];
+        let policy = Policy::default();
+        let report = policy.check(&packages);
+        assert!(!report.passes());
+        assert_eq!(names(report.unknown.iter().copied()), ["a", "b"]);
+    }
+
+    #[test]
+    fn applies_exceptions() {
+        let packages = [package("a", "GPL-3.0-only"), package("b", "MIT")];
+        let policy = policy(
+            "exceptions:
+  - package: a@1.0.0
+    license: GPL-3.0-only
+    justification: Only used by the build.
+  - package: c
+    justification: Removed since.
+",
+        );
+        let report = policy.check(&packages);
+        assert!(report.passes());
+        assert_eq!(names(report.excepted.iter().map(|(p, _)| *p)), ["a"]);
+        let unused: Vec<&str> = report
+            .unused_exceptions
+            .iter()
+            .map(|exception| exception.package.as_str())
+            .collect();
+        assert_eq!(unused, ["c"]);
+    }
+
+    #[test]
+    fn drops_exceptions_once_the_license_changes() {
+        let packages = [package("a", "AGPL-3.0-only")];
+        let policy = policy(
+            "exceptions:
+  - package: a
+    license: GPL-3.0-only
+    justification: Only used by the build.
+",
+        );
+        let report = policy.check(&packages);
+        assert!(!report.passes());
+        assert_eq!(report.denied.len(), 1);
+        assert_eq!(report.unused_exceptions.len(), 1);
+    }
+}
@@ -0,0 +1,389 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Map, Value};
+
+use crate::parser::{Ecosystem, ParsedPackageJson};
+use crate::write::utc_timestamp;
+
+const NOASSERTION: &str = "NOASSERTION";
+const DOCUMENT_ID: &str = "SPDXRef-DOCUMENT";
+
+struct SpdxPackage<'a> {
+    id: String,
+    package: &'a ParsedPackageJson,
+    license_declared: String,
+    // What the license file reads as, when the declared license names no known one.
+    license_concluded: String,
+}
+
+struct SpdxDocument<'a> {
+    name: String,
+    namespace: String,
+    created: String,
+    packages: Vec<SpdxPackage<'a>>,
+    // (element, relationship, related element)
+    relationships: Vec<(String, &'static str, String)>,
+    // LicenseRef id to the license string it stands for and its text.
+    extracted_licenses: BTreeMap<String, (String, String)>,
+}
+
+/// Replaces what SPDX ids do not allow (anything but letters, digits, `.` and `-`).
+fn sanitize_id(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .trim_matches('-')
+        .to_string()
+}
+
+// The license file when the report inlines it, what the metadata declares otherwise.
+fn extracted_text(package: &ParsedPackageJson) -> String {
+    match package.license_text.trim() {
+        "" => format!(
+            "The package metadata declares the license as \"{}\".",
+            package.license.trim()
+        ),
+        text => text.to_string(),
+    }
+}
+
+fn copyright_text(package: &ParsedPackageJson) -> String {
+    match package.copyrights.is_empty() {
+        true => NOASSERTION.to_string(),
+        false => package.copyrights.join("\n"),
+    }
+}
+
+fn download_location(package: &ParsedPackageJson) -> String {
+    if package.resolved.starts_with("http") {
+        // yarn appends the sha1 of the tarball as a fragment.
+        let url = package.resolved.split('#').next().unwrap_or_default();
+        return url.to_string();
+    }
+    NOASSERTION.to_string()
+}
+
+fn build_document<'a>(packages: &'a [ParsedPackageJson], document_name: &str) -> SpdxDocument<'a> {
+    let mut sorted: Vec<&ParsedPackageJson> = packages.iter().collect();
+    sorted.sort_by_key(|package| package.purl());
+
+    let mut used_ids = HashSet::new();
+    let mut extracted_licenses = BTreeMap::new();
+    // The LicenseRef id of each free-form license, as first met.
+    let mut license_refs: HashMap<&str, String> = HashMap::new();
+    let mut spdx_packages = Vec::new();
+
+    for package in sorted {
+        let base_id = format!(
+            "SPDXRef-Package-{}-{}",
+            package.ecosystem.as_str(),
+            sanitize_id(&package.key())
+        );
+        let mut id = base_id.clone();
+        let mut suffix = 1;
+        while !used_ids.insert(id.clone()) {
+            suffix += 1;
+            id = format!("{base_id}-{suffix}");
+        }
+
+        let license = package.license.trim();
+        let license_declared = if let Some(expression) = &package.license_expression {
+            // Documents define the LicenseRef ids they use.
+            for license_ref in expression.license_refs() {
+                extracted_licenses
+                    .entry(license_ref.to_string())
+                    .or_insert((license.to_string(), extracted_text(package)));
+            }
+            expression.to_string()
+        } else if license.is_empty() {
+            NOASSERTION.to_string()
+        } else {
+            match license_refs.get(license) {
+                Some(license_ref) => license_ref.clone(),
+                None => {
+                    // Licenses sanitized to the same id, e.g. `Foo Bar` and `Foo-Bar`, or with
+                    // nothing left, e.g. `©`, are numbered.
+                    let base_ref = match sanitize_id(license) {
+                        id if !id.is_empty() => format!("LicenseRef-{id}"),
+                        _ => "LicenseRef-unknown".to_string(),
+                    };
+                    let mut number = 1;
+                    let mut license_ref = match base_ref == "LicenseRef-unknown" {
+                        true => format!("{base_ref}-{number}"),
+                        false => base_ref.clone(),
+                    };
+                    while extracted_licenses.contains_key(&license_ref) {
+                        number += 1;
+                        license_ref = format!("{base_ref}-{number}");
+                    }
+                    extracted_licenses.insert(
+                        license_ref.clone(),
+                        (license.to_string(), extracted_text(package)),
+                    );
+                    license_refs.insert(license, license_ref.clone());
+                    license_ref
+                }
+            }
+        };
+
+        spdx_packages.push(SpdxPackage {
+            id,
+            package,
+            license_declared,
+            license_concluded: package
+                .detected_license
+                .as_ref()
+                .map(|detected| detected.license.to_string())
+                .unwrap_or(NOASSERTION.to_string()),
+        });
+    }
+
+    let ids: HashMap<(Ecosystem, String), &String> = spdx_packages
+        .iter()
+        .map(|spdx| ((spdx.package.ecosystem, spdx.package.key()), &spdx.id))
+        .collect();
+
+    let mut dependency_relationships = Vec::new();
+    let mut depended_upon = HashSet::new();
+    for spdx in &spdx_packages {
+        let mut related_ids: Vec<&String> = spdx
+            .package
+            .dependencies
+            .iter()
+            .filter_map(|key| ids.get(&(spdx.package.ecosystem, key.clone())).copied())
+            .collect();
+        related_ids.sort();
+        for related_id in related_ids {
+            depended_upon.insert(related_id.clone());
+            dependency_relationships.push((spdx.id.clone(), "DEPENDS_ON", related_id.clone()));
+        }
+    }
+
+    // The document describes the packages nothing else depends on, or every package when no
+    // dependency graph is known.
+    let mut relationships: Vec<(String, &'static str, String)> = spdx_packages
+        .iter()
+        .filter(|spdx| !depended_upon.contains(&spdx.id))
+        .map(|spdx| (DOCUMENT_ID.to_string(), "DESCRIBES", spdx.id.clone()))
+        .collect();
+    if relationships.is_empty() {
+        relationships = spdx_packages
+            .iter()
+            .map(|spdx| (DOCUMENT_ID.to_string(), "DESCRIBES", spdx.id.clone()))
+            .collect();
+    }
+    relationships.extend(dependency_relationships);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default();
+
+    SpdxDocument {
+        name: document_name.to_string(),
+        namespace: format!(
+            "https://spdx.org/spdxdocs/{}-{nanos:x}",
+            sanitize_id(document_name)
+        ),
+        created: utc_timestamp(),
+        packages: spdx_packages,
+        relationships,
+        extracted_licenses,
+    }
+}
+
+fn creator() -> String {
+    format!(
+        "Tool: {}-{}",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    )
+}
+
+/// Builds an SPDX 2.3 document in the JSON format.
+pub(crate) fn to_json(packages: &[ParsedPackageJson], document_name: &str) -> Value {
+    let document = build_document(packages, document_name);
+
+    let spdx_packages: Vec<Value> = document
+        .packages
+        .iter()
+        .map(|spdx| {
+            let package = spdx.package;
+            let mut spdx_package = Map::new();
+            spdx_package.insert("SPDXID".to_string(), json!(spdx.id));
+            spdx_package.insert("name".to_string(), json!(package.name));
+            if !package.version.is_empty() {
+                spdx_package.insert("versionInfo".to_string(), json!(package.version));
+            }
+            spdx_package.insert(
+                "downloadLocation".to_string(),
+                json!(download_location(package)),
+            );
+            spdx_package.insert("filesAnalyzed".to_string(), json!(false));
+
+            let checksums: Vec<Value> = package
+                .hashes()
+                .into_iter()
+                .map(|(algorithm, value)| {
+                    json!({ "algorithm": algorithm.replace('-', ""), "checksumValue": value })
+                })
+                .collect();
+            if !checksums.is_empty() {
+                spdx_package.insert("checksums".to_string(), json!(checksums));
+            }
+            if !package.homepage.is_empty() {
+                spdx_package.insert("homepage".to_string(), json!(package.homepage));
+            }
+            spdx_package.insert(
+                "licenseConcluded".to_string(),
+                json!(spdx.license_concluded),
+            );
+            spdx_package.insert("licenseDeclared".to_string(), json!(spdx.license_declared));
+            spdx_package.insert(
+                "copyrightText".to_string(),
+                json!(copyright_text(spdx.package)),
+            );
+            if !package.description.is_empty() {
+                spdx_package.insert("description".to_string(), json!(package.description));
+            }
+            spdx_package.insert(
+                "externalRefs".to_string(),
+                json!([{
+                    "referenceCategory": "PACKAGE-MANAGER",
+                    "referenceType": "purl",
+                    "referenceLocator": package.purl(),
+                }]),
+            );
+            Value::Object(spdx_package)
+        })
+        .collect();
+
+    let relationships: Vec<Value> = document
+        .relationships
+        .iter()
+        .map(|(element, relationship, related)| {
+            json!({
+                "spdxElementId": element,
+                "relationshipType": relationship,
+                "relatedSpdxElement": related,
+            })
+        })
+        .collect();
+
+    let mut spdx_document = json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": DOCUMENT_ID,
+        "name": document.name,
+        "documentNamespace": document.namespace,
+        "creationInfo": {
+            "created": document.created,
+            "creators": [creator()],
+        },
+        "packages": spdx_packages,
+        "relationships": relationships,
+    });
+
+    if !document.extracted_licenses.is_empty() {
+        let extracted: Vec<Value> = document
+            .extracted_licenses
+            .iter()
+            .map(|(license_id, (license, extracted_text))| {
+                json!({
+                    "licenseId": license_id,
+                    "name": license,
+                    "extractedText": extracted_text,
+                })
+            })
+            .collect();
+        spdx_document["hasExtractedLicensingInfos"] = json!(extracted);
+    }
+    spdx_document
+}
+
+fn text(value: &str) -> String {
+    format!("<text>{value}</text>")
+}
+
+/// Builds an SPDX 2.3 document in the tag-value format.
+pub(crate) fn to_tag_value(packages: &[ParsedPackageJson], document_name: &str) -> String {
+    let document = build_document(packages, document_name);
+    let mut lines: Vec<String> = vec![
+        "SPDXVersion: SPDX-2.3".to_string(),
+        "DataLicense: CC0-1.0".to_string(),
+        format!("SPDXID: {DOCUMENT_ID}"),
+        format!("DocumentName: {}", document.name),
+        format!("DocumentNamespace: {}", document.namespace),
+        format!("Creator: {}", creator()),
+        format!("Created: {}", document.created),
+    ];
+
+    for spdx in &document.packages {
+        let package = spdx.package;
+        lines.push(String::new());
+        lines.push(format!("PackageName: {}", package.name));
+        lines.push(format!("SPDXID: {}", spdx.id));
+        if !package.version.is_empty() {
+            lines.push(format!("PackageVersion: {}", package.version));
+        }
+        lines.push(format!(
+            "PackageDownloadLocation: {}",
+            download_location(package)
+        ));
+        lines.push("FilesAnalyzed: false".to_string());
+        for (algorithm, value) in package.hashes() {
+            lines.push(format!(
+                "PackageChecksum: {}: {value}",
+                algorithm.replace('-', "")
+            ));
+        }
+        if !package.homepage.is_empty() {
+            lines.push(format!("PackageHomePage: {}", package.homepage));
+        }
+        lines.push(format!(
+            "PackageLicenseConcluded: {}",
+            spdx.license_concluded
+        ));
+        lines.push(format!("PackageLicenseDeclared: {}", spdx.license_declared));
+        let copyright = match package.copyrights.is_empty() {
+            true => NOASSERTION.to_string(),
+            false => text(&copyright_text(package)),
+        };
+        lines.push(format!("PackageCopyrightText: {copyright}"));
+        if !package.description.is_empty() {
+            lines.push(format!(
+                "PackageDescription: {}",
+                text(&package.description)
+            ));
+        }
+        lines.push(format!(
+            "ExternalRef: PACKAGE-MANAGER purl {}",
+            package.purl()
+        ));
+    }
+
+    if !document.relationships.is_empty() {
+        lines.push(String::new());
+    }
+    for (element, relationship, related) in &document.relationships {
+        lines.push(format!("Relationship: {element} {relationship} {related}"));
+    }
+
+    for (license_id, (license, extracted_text)) in &document.extracted_licenses {
+        lines.push(String::new());
+        lines.push(format!("LicenseID: {license_id}"));
+        lines.push(format!("ExtractedText: {}", text(extracted_text)));
+        lines.push(format!("LicenseName: {license}"));
+    }
+
+    lines.push(String::new());
+    lines.join("\n")
+}
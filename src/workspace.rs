@@ -0,0 +1,215 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::parser::{Ecosystem, Importer, ParsedPackageJson};
+
+// Never looked into when expanding `**`.
+const IGNORED_DIRECTORIES: [&str; 2] = ["node_modules", ".git"];
+
+#[derive(Debug, Clone)]
+pub(crate) struct WorkspaceMember {
+    pub(crate) name: String,
+    // Relative to the project root.
+    pub(crate) path: String,
+}
+
+/// Reads the workspace globs of a project, from the `workspaces` field of its package.json
+/// (npm and yarn) or from pnpm-workspace.yaml.
+fn workspace_patterns(root_directory: &Path) -> Vec<String> {
+    let mut patterns = Vec::new();
+
+    let package_json = fs::read_to_string(root_directory.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok());
+    if let Some(package_json) = package_json {
+        // `"workspaces": [...]` or yarn's `"workspaces": {"packages": [...]}`.
+        let workspaces = match &package_json["workspaces"] {
+            Value::Object(workspaces) => workspaces.get("packages").cloned(),
+            workspaces => Some(workspaces.clone()),
+        };
+        patterns.extend(
+            workspaces
+                .as_ref()
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .map(str::to_string),
+        );
+    }
+
+    let pnpm_workspace = fs::read_to_string(root_directory.join("pnpm-workspace.yaml"))
+        .ok()
+        .and_then(|content| serde_yaml::from_str::<serde_yaml::Value>(&content).ok());
+    if let Some(pnpm_workspace) = pnpm_workspace {
+        patterns.extend(
+            pnpm_workspace["packages"]
+                .as_sequence()
+                .into_iter()
+                .flatten()
+                .filter_map(serde_yaml::Value::as_str)
+                .map(str::to_string),
+        );
+    }
+    patterns
+}
+
+/// Matches a single path segment against a glob segment where `*` matches any run of
+/// characters.
+fn matches_segment(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    for (index, part) in parts.iter().enumerate() {
+        if index == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(found) => rest = &rest[found + part.len()..],
+            None => return false,
+        }
+    }
+    rest.is_empty()
+}
+
+fn subdirectories(directory: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(directory)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| !IGNORED_DIRECTORIES.contains(&name.as_str()))
+        .collect();
+    names.sort();
+    names
+}
+
+/// Expands a workspace glob such as `packages/*` or `apps/**` into directories relative to
+/// the root.
+fn expand_pattern(root_directory: &Path, pattern: &str) -> Vec<String> {
+    let mut matches = vec![String::new()];
+
+    for segment in pattern
+        .trim_start_matches("./")
+        .split('/')
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+    {
+        let mut next = Vec::new();
+        for base in matches {
+            let join = |name: &str| match base.is_empty() {
+                true => name.to_string(),
+                false => format!("{base}/{name}"),
+            };
+            if segment == "**" {
+                // Any depth, including none.
+                let mut pending = vec![base.clone()];
+                while let Some(directory) = pending.pop() {
+                    for name in subdirectories(&root_directory.join(&directory)) {
+                        pending.push(match directory.is_empty() {
+                            true => name,
+                            false => format!("{directory}/{name}"),
+                        });
+                    }
+                    next.push(directory);
+                }
+            } else if segment.contains('*') {
+                next.extend(
+                    subdirectories(&root_directory.join(&base))
+                        .into_iter()
+                        .filter(|name| matches_segment(segment, name))
+                        .map(|name| join(&name)),
+                );
+            } else if root_directory.join(&base).join(segment).is_dir() {
+                next.push(join(segment));
+            }
+        }
+        matches = next;
+    }
+    matches
+}
+
+/// Returns the members of a npm, yarn or pnpm workspace, an empty list when the project is
+/// not one.
+pub(crate) fn workspace_members(root_directory: &Path) -> Vec<WorkspaceMember> {
+    let mut included = Vec::new();
+    let mut excluded = HashSet::new();
+
+    for pattern in workspace_patterns(root_directory) {
+        match pattern.strip_prefix('!') {
+            Some(pattern) => excluded.extend(expand_pattern(root_directory, pattern)),
+            None => included.extend(expand_pattern(root_directory, &pattern)),
+        }
+    }
+    included.sort();
+    included.dedup();
+
+    included
+        .into_iter()
+        .filter(|path| !path.is_empty() && !excluded.contains(path))
+        .filter_map(|path| {
+            let content =
+                fs::read_to_string(root_directory.join(&path).join("package.json")).ok()?;
+            let package_json = serde_json::from_str::<Value>(&content).ok()?;
+            let name = package_json["name"]
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| path.rsplit('/').next().unwrap_or(&path).to_string());
+            Some(WorkspaceMember { name, path })
+        })
+        .collect()
+}
+
+/// Returns the npm packages a workspace member needs, directly or through the packages and
+/// other members it depends on.
+pub(crate) fn reachable_packages(
+    packages: &[ParsedPackageJson],
+    importers: &[Importer],
+    member_path: &str,
+) -> Vec<ParsedPackageJson> {
+    let indexes: HashMap<String, usize> = packages
+        .iter()
+        .enumerate()
+        .filter(|(_, package)| package.ecosystem == Ecosystem::Npm)
+        .map(|(index, package)| (package.key(), index))
+        .collect();
+
+    let mut visited_importers = HashSet::new();
+    let mut pending_importers = vec![member_path.to_string()];
+    let mut pending_packages: VecDeque<&String> = VecDeque::new();
+    while let Some(path) = pending_importers.pop() {
+        if !visited_importers.insert(path.clone()) {
+            continue;
+        }
+        for importer in importers
+            .iter()
+            .filter(|importer| importer.ecosystem == Ecosystem::Npm && importer.path == path)
+        {
+            pending_packages.extend(importer.dependencies.iter().map(|(key, _)| key));
+            pending_importers.extend(importer.workspace_dependencies.iter().cloned());
+        }
+    }
+
+    let mut reached = HashSet::new();
+    while let Some(key) = pending_packages.pop_front() {
+        let Some(&index) = indexes.get(key) else {
+            continue;
+        };
+        if reached.insert(index) {
+            pending_packages.extend(&packages[index].dependencies);
+        }
+    }
+
+    let mut reached: Vec<usize> = reached.into_iter().collect();
+    reached.sort();
+    reached
+        .into_iter()
+        .map(|index| packages[index].clone())
+        .collect()
+}
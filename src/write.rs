@@ -1,7 +1,18 @@
 use serde_json::{Map, Value};
-use std::fs::File;
+use std::fs::{self, File};
 use std::io;
 use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::android;
+use crate::csv;
+use crate::cyclonedx;
+use crate::html;
+use crate::markdown;
+use crate::notice;
+use crate::parser::ParsedPackageJson;
+use crate::spdx;
 
 pub(crate) fn write_node_dependencies_to_file(
     vec: Map<String, Value>,
@@ -14,3 +25,171 @@ pub(crate) fn write_node_dependencies_to_file(
     writer.flush()?;
     Ok(())
 }
+
+pub(crate) fn write_cyclonedx_json_to_file(
+    packages: &[ParsedPackageJson],
+    file_path: &str,
+) -> io::Result<()> {
+    let file = File::create(file_path)?;
+    let mut writer = BufWriter::new(file);
+
+    serde_json::to_writer_pretty(&mut writer, &cyclonedx::to_json(packages))?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub(crate) fn write_cyclonedx_xml_to_file(
+    packages: &[ParsedPackageJson],
+    file_path: &str,
+) -> io::Result<()> {
+    let file = File::create(file_path)?;
+    let mut writer = BufWriter::new(file);
+
+    writer.write_all(cyclonedx::to_xml(packages).as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub(crate) fn write_spdx_json_to_file(
+    packages: &[ParsedPackageJson],
+    document_name: &str,
+    file_path: &str,
+) -> io::Result<()> {
+    let file = File::create(file_path)?;
+    let mut writer = BufWriter::new(file);
+
+    serde_json::to_writer_pretty(&mut writer, &spdx::to_json(packages, document_name))?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub(crate) fn write_spdx_tag_value_to_file(
+    packages: &[ParsedPackageJson],
+    document_name: &str,
+    file_path: &str,
+) -> io::Result<()> {
+    let file = File::create(file_path)?;
+    let mut writer = BufWriter::new(file);
+
+    writer.write_all(spdx::to_tag_value(packages, document_name).as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub(crate) fn write_notice_to_file(
+    packages: &[ParsedPackageJson],
+    file_path: &str,
+) -> io::Result<()> {
+    let file = File::create(file_path)?;
+    let mut writer = BufWriter::new(file);
+
+    writer.write_all(notice::to_text(packages).as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub(crate) fn write_markdown_to_file(
+    packages: &[ParsedPackageJson],
+    document_name: &str,
+    file_path: &str,
+) -> io::Result<()> {
+    let file = File::create(file_path)?;
+    let mut writer = BufWriter::new(file);
+
+    writer.write_all(markdown::to_markdown(packages, document_name).as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub(crate) fn write_html_to_file(
+    packages: &[ParsedPackageJson],
+    document_name: &str,
+    file_path: &str,
+) -> io::Result<()> {
+    let file = File::create(file_path)?;
+    let mut writer = BufWriter::new(file);
+
+    writer.write_all(html::to_html(packages, document_name).as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub(crate) fn write_delimited_to_file(
+    packages: &[ParsedPackageJson],
+    delimiter: char,
+    file_path: &str,
+) -> io::Result<()> {
+    let file = File::create(file_path)?;
+    let mut writer = BufWriter::new(file);
+
+    writer.write_all(csv::to_delimited(packages, delimiter).as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub(crate) fn write_android_oss_licenses_to_dir(
+    packages: &[ParsedPackageJson],
+    res_directory: &str,
+) -> io::Result<()> {
+    let raw_directory = Path::new(res_directory).join("raw");
+    fs::create_dir_all(&raw_directory)?;
+
+    let (licenses, metadata) = android::to_oss_licenses(packages);
+    fs::write(raw_directory.join("third_party_licenses"), licenses)?;
+    fs::write(raw_directory.join("third_party_license_metadata"), metadata)?;
+    Ok(())
+}
+
+pub(crate) fn write_android_licenses_xml_to_dir(
+    packages: &[ParsedPackageJson],
+    res_directory: &str,
+) -> io::Result<()> {
+    let values_directory = Path::new(res_directory).join("values");
+    fs::create_dir_all(&values_directory)?;
+
+    fs::write(
+        values_directory.join("licenses.xml"),
+        android::to_licenses_xml(packages),
+    )
+}
+
+pub(crate) fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// The current time as an RFC 3339 UTC timestamp, e.g. `2023-11-27T10:04:59Z`.
+pub(crate) fn utc_timestamp() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default();
+    let (days, seconds_of_day) = (seconds.div_euclid(86_400), seconds.rem_euclid(86_400));
+
+    // Civil date from days since 1970-01-01, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        seconds_of_day / 3_600,
+        seconds_of_day % 3_600 / 60,
+        seconds_of_day % 60
+    )
+}